@@ -0,0 +1,71 @@
+//! Throughput of byte payloads through the RPC bridge, JSON (an array of numbers) compared to the
+//! binary (base64) path. Covers the Rust side: building the output event script, and decoding the
+//! params of an input event.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use headless_webview::webview::{binary_output_event_script, output_event_script, RpcRequest};
+
+const SIZES: [usize; 3] = [1024, 64 * 1024, 1024 * 1024];
+
+fn payload(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 31 % 256) as u8).collect()
+}
+
+fn output_events(c: &mut Criterion) {
+    let mut group = c.benchmark_group("output_event");
+
+    for size in SIZES {
+        let bytes = payload(size);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("json", size), &bytes, |b, bytes| {
+            b.iter(|| output_event_script("minimap", &serde_json::to_string(bytes).unwrap()))
+        });
+
+        group.bench_with_input(BenchmarkId::new("binary", size), &bytes, |b, bytes| {
+            b.iter(|| binary_output_event_script("minimap", bytes))
+        });
+    }
+
+    group.finish();
+}
+
+fn input_events(c: &mut Criterion) {
+    let mut group = c.benchmark_group("input_event");
+
+    for size in SIZES {
+        let bytes = payload(size);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        // as sent by `window.rpc.notify(method, Array.from(bytes))`
+        let json = serde_json::json!({ "jsonrpc": "2.0", "method": "audio", "params": [bytes] });
+        let json = serde_json::to_string(&json).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("json", size), &json, |b, json| {
+            b.iter(|| {
+                let request: RpcRequest = serde_json::from_str(json).unwrap();
+                let params = request.params.unwrap();
+                serde_json::from_value::<Vec<u8>>(params[0].clone()).unwrap()
+            })
+        });
+
+        // as sent by `window.rpc.notifyBinary(method, bytes)`
+        let binary = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "audio",
+            "params": [base64::encode(&bytes)],
+        });
+        let binary = serde_json::to_string(&binary).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("binary", size), &binary, |b, binary| {
+            b.iter(|| {
+                let request: RpcRequest = serde_json::from_str(binary).unwrap();
+                request.binary_params().unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, output_events, input_events);
+criterion_main!(benches);
@@ -1,13 +1,17 @@
 use std::{
+    cell::{Cell, RefCell},
     rc::Rc,
     sync::{Mutex, RwLock},
 };
 
 use crate::{
-    types::{KeyboardInput, MouseEvent, Texture, TextureFormat, WindowSize},
+    types::{
+        KeyboardInput, MouseEvent, ScrollEvent, SnapshotRegion, Texture, TextureAlpha,
+        TextureColorSpace, TextureFormat, TextureRegion, TouchEvent, WindowSize,
+    },
     webview::{
         web_context::{WebContext, WebContextData, WebContextImpl},
-        EngineWebview, WebViewAttributes,
+        EngineWebview, RpcRequest, RpcResponse, WebViewAttributes,
     },
     window::{HeadlessWindow, WindowAttributes, WindowBuilder, WindowId},
     Result,
@@ -51,8 +55,35 @@ impl HeadlessWindow for DummyWindow {
     }
 }
 
+/// RPC handler of the webview attributes
+type RpcHandler = Box<dyn Fn(&DummyWindow, RpcRequest) -> Option<RpcResponse>>;
+
+/// Webview without a page. Like a page running `rpc.js`, it sends the `_webview` initialize
+/// notification to the RPC handler when first ticked
 pub struct DummyWebView {
     window: Rc<DummyWindow>,
+    rpc_handler: Option<RpcHandler>,
+    page_loaded: Cell<bool>,
+    scroll_events: RefCell<Vec<ScrollEvent>>,
+    touch_events: RefCell<Vec<TouchEvent>>,
+    is_focused: Cell<bool>,
+}
+
+impl DummyWebView {
+    /// Scroll events received so far
+    pub fn scroll_events(&self) -> Vec<ScrollEvent> {
+        self.scroll_events.borrow().clone()
+    }
+
+    /// Touch events received so far
+    pub fn touch_events(&self) -> Vec<TouchEvent> {
+        self.touch_events.borrow().clone()
+    }
+
+    /// Whether the webview has the keyboard focus
+    pub fn is_focused(&self) -> bool {
+        self.is_focused.get()
+    }
 }
 
 impl EngineWebview for DummyWebView {
@@ -61,23 +92,46 @@ impl EngineWebview for DummyWebView {
 
     fn new(
         window: Rc<Self::Window>,
-        _webview: WebViewAttributes<Self::Window>,
+        mut webview: WebViewAttributes<Self::Window>,
         _web_context: Option<Rc<Mutex<WebContext<Self::WebContext>>>>,
     ) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(DummyWebView { window })
+        Ok(DummyWebView {
+            window,
+            rpc_handler: webview.rpc_handler.take(),
+            page_loaded: Cell::new(false),
+            scroll_events: RefCell::new(Vec::new()),
+            touch_events: RefCell::new(Vec::new()),
+            is_focused: Cell::new(false),
+        })
     }
 
     fn send_keyboard_input(&self, keyboard_input: KeyboardInput) {
         println!("Received keyboard event: {:?}", keyboard_input);
     }
 
+    fn send_text_input(&self, text: &str) {
+        println!("Received text input: {:?}", text);
+    }
+
     fn send_mouse_event(&self, mouse_event: MouseEvent) {
         println!("Received mouse event: {:?}", mouse_event);
     }
 
+    fn send_scroll(&self, scroll_event: ScrollEvent) {
+        self.scroll_events.borrow_mut().push(scroll_event);
+    }
+
+    fn send_touch_event(&self, touch_event: TouchEvent) {
+        self.touch_events.borrow_mut().push(touch_event);
+    }
+
+    fn set_focus(&self, focused: bool) {
+        self.is_focused.set(focused);
+    }
+
     fn window(&self) -> &Self::Window {
         &self.window
     }
@@ -87,16 +141,47 @@ impl EngineWebview for DummyWebView {
     }
 
     fn get_texture(&mut self) -> Result<Option<Texture>> {
+        self.get_texture_into(&mut Vec::new())
+    }
+
+    fn get_texture_into(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Texture>> {
+        let pixels = (self.window.width() * self.window.height()) as usize;
+
+        buffer.clear();
+        buffer.extend([50, 180, 50, 255].repeat(pixels));
+
         Ok(Some(Texture {
             width: self.window.width(),
             height: self.window.height(),
             format: TextureFormat::Rgba8,
-            data: [50, 180, 50, 255].repeat((self.window.width() * self.window.height()) as usize),
+            data: std::mem::take(buffer),
+            region: TextureRegion::Full,
+            alpha: TextureAlpha::Straight,
+            color_space: TextureColorSpace::Srgb,
         }))
     }
 
+    fn snapshot(&mut self, _region: SnapshotRegion) -> Result<Texture> {
+        // no document, both regions are the window
+        Ok(self.get_texture_into(&mut Vec::new())?.unwrap())
+    }
+
     fn tick_once(&mut self) {
-        // nothing
+        if self.page_loaded.replace(true) {
+            return;
+        }
+
+        if let Some(handler) = &self.rpc_handler {
+            // unwrap ok, a valid request
+            let request = serde_json::from_value(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "_webview",
+                "params": [{ "initialize": null }],
+            }))
+            .unwrap();
+
+            handler(&self.window, request);
+        }
     }
 
     fn version(&self) -> Result<String> {
@@ -116,7 +201,7 @@ impl EngineWebview for DummyWebView {
 
     fn reload(&self) {}
 
-    fn send_mouse_position(&self, _position: crate::types::Vec2) {}
+    fn send_mouse_motion(&self, _mouse_motion: crate::types::MouseMotion) {}
 
     fn set_is_visible(&mut self, _is_visible: bool) {}
 }
@@ -58,4 +58,14 @@ pub enum Error {
     InvalidStatusCode(#[from] InvalidStatusCode),
     #[error("Invalid method: {0}")]
     InvalidMethod(#[from] InvalidMethod),
+    #[error("Snapshot failed: {0}")]
+    SnapshotError(String),
+    #[error("Texture is not a full frame")]
+    PartialTexture,
+    #[error(transparent)]
+    Png(#[from] png::EncodingError),
+    #[error("Binary RPC params must be a base64 string")]
+    BinaryParams,
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
 }
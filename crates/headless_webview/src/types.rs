@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use crate::{Error, Result};
+
 /// Represents a mouse event at a specific position
 #[derive(Debug, Clone)]
 pub struct MouseEvent {
@@ -11,6 +13,77 @@ pub struct MouseEvent {
 
     /// Position where mouse cursor was when the event occured
     pub position: Vec2,
+
+    /// Modifier keys that were held down when the event occured
+    pub modifiers: Modifiers,
+}
+
+/// Represents a mouse cursor movement
+#[derive(Debug, Clone)]
+pub struct MouseMotion {
+    /// Position of the mouse cursor
+    pub position: Vec2,
+
+    /// Buttons held down during the movement (e.g. dragging)
+    pub buttons: Vec<MouseButton>,
+
+    /// Modifier keys held down during the movement
+    pub modifiers: Modifiers,
+}
+
+/// Represents a scroll (mouse wheel / trackpad) event at a specific position
+#[derive(Debug, Clone)]
+pub struct ScrollEvent {
+    /// Scroll amount, positive values scroll towards the right and the bottom of the page
+    pub delta: Vec2,
+
+    /// Unit of `delta`
+    pub unit: ScrollUnit,
+
+    /// Position where mouse cursor was when the event occured
+    pub position: Vec2,
+
+    /// Modifier keys that were held down when the event occured
+    pub modifiers: Modifiers,
+}
+
+/// Unit of scroll amount
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollUnit {
+    /// Scroll by lines (mouse wheel notches)
+    Line,
+
+    /// Scroll by pixels (trackpads, smooth scrolling mice)
+    Pixel,
+}
+
+/// Represents a single touch point event
+#[derive(Debug, Clone)]
+pub struct TouchEvent {
+    /// Identifier of the touch point, unique while the finger is on the screen
+    pub id: u64,
+
+    /// Phase of the touch point
+    pub phase: TouchPhase,
+
+    /// Position of the touch point
+    pub position: Vec2,
+}
+
+/// Phase of a touch point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchPhase {
+    /// Finger touched the screen
+    Started,
+
+    /// Finger moved on the screen
+    Moved,
+
+    /// Finger was lifted from the screen
+    Ended,
+
+    /// Touch was cancelled by the system
+    Cancelled,
 }
 
 /// State for input event
@@ -23,8 +96,263 @@ pub enum ElementState {
 /// Keyboard event
 #[derive(Debug, Clone)]
 pub struct KeyboardInput {
+    /// Key state
     pub state: ElementState,
-    // TODO: add key
+
+    /// Platform-specific scan code of the key
+    pub scan_code: u32,
+
+    /// Symbolic key code, `None` if the key could not be identified
+    pub key_code: Option<KeyCode>,
+
+    /// Modifier keys that were held down when the event occured
+    pub modifiers: Modifiers,
+
+    /// Character typed by the key press, following the keyboard layout. It is inserted as the text
+    /// of the key, `None` for keys that do not type text and for key releases
+    pub text: Option<char>,
+}
+
+/// Symbolic name of a keyboard key, follows the naming of `winit` / Bevy key codes
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+pub enum KeyCode {
+    /// The '1' key over the letters.
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    Key0,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    /// The Escape key, next to F1.
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    /// Print Screen/SysRq.
+    Snapshot,
+    /// Scroll Lock.
+    Scroll,
+    /// Pause/Break key, next to Scroll lock.
+    Pause,
+    /// `Insert`, next to Backspace.
+    Insert,
+    Home,
+    Delete,
+    End,
+    PageDown,
+    PageUp,
+    /// The arrow keys.
+    Left,
+    Up,
+    Right,
+    Down,
+    /// The Backspace key, right over Enter.
+    Back,
+    /// The Enter key.
+    Return,
+    /// The space bar.
+    Space,
+    /// The "Compose" key on Linux.
+    Compose,
+    Caret,
+    Numlock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    AbntC1,
+    AbntC2,
+    NumpadAdd,
+    Apostrophe,
+    Apps,
+    Asterisk,
+    Plus,
+    At,
+    Ax,
+    Backslash,
+    Calculator,
+    Capital,
+    Colon,
+    Comma,
+    Convert,
+    NumpadDecimal,
+    NumpadDivide,
+    Equals,
+    Grave,
+    Kana,
+    Kanji,
+    LAlt,
+    LBracket,
+    LControl,
+    LShift,
+    LWin,
+    Mail,
+    MediaSelect,
+    MediaStop,
+    Minus,
+    NumpadMultiply,
+    Mute,
+    MyComputer,
+    /// Also called "Prior"
+    NavigateForward,
+    /// Also called "Next"
+    NavigateBackward,
+    NextTrack,
+    NoConvert,
+    NumpadComma,
+    NumpadEnter,
+    NumpadEquals,
+    Oem102,
+    Period,
+    PlayPause,
+    Power,
+    PrevTrack,
+    RAlt,
+    RBracket,
+    RControl,
+    RShift,
+    RWin,
+    Semicolon,
+    Slash,
+    Sleep,
+    Stop,
+    NumpadSubtract,
+    Sysrq,
+    Tab,
+    Underline,
+    Unlabeled,
+    VolumeDown,
+    VolumeUp,
+    Wake,
+    WebBack,
+    WebFavorites,
+    WebForward,
+    WebHome,
+    WebRefresh,
+    WebSearch,
+    WebStop,
+    Yen,
+    Copy,
+    Paste,
+    Cut,
+}
+
+/// Set of modifier keys held down during an input event
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// Either of the Shift keys
+    pub const SHIFT: Modifiers = Modifiers(1 << 0);
+
+    /// Either of the Control keys
+    pub const CTRL: Modifiers = Modifiers(1 << 1);
+
+    /// Either of the Alt keys
+    pub const ALT: Modifiers = Modifiers(1 << 2);
+
+    /// Either of the Logo keys (Windows / Command / Super)
+    pub const LOGO: Modifiers = Modifiers(1 << 3);
+
+    /// No modifiers set
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Raw bit representation
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Whether no modifiers are set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all modifiers of `other` are set
+    pub const fn contains(&self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set or unset the modifiers of `other`
+    pub fn set(&mut self, other: Modifiers, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
 }
 
 /// A 2D vector with x and y
@@ -41,7 +369,7 @@ impl Vec2 {
 }
 
 /// Mouse button identifier
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MouseButton {
     Left,
     Right,
@@ -49,6 +377,25 @@ pub enum MouseButton {
     Other(u16), // FIXME: unsafe
 }
 
+/// Mouse cursor icon requested by the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CursorIcon {
+    #[default]
+    Default,
+    /// Over a link
+    Hand,
+    /// Over editable or selectable text
+    Text,
+    Crosshair,
+    Move,
+    Wait,
+    Progress,
+    Help,
+    NotAllowed,
+    Grab,
+    Grabbing,
+}
+
 /// Webview output texture
 #[derive(Debug, Clone)]
 pub struct Texture {
@@ -61,14 +408,265 @@ pub struct Texture {
     /// Texture pixel format
     pub format: TextureFormat,
 
-    /// Byte data of the texture
+    /// Byte data of the texture, or of the damaged rectangles (see [`TextureRegion`])
     pub data: Vec<u8>,
+
+    /// Part of the texture that `data` covers
+    pub region: TextureRegion,
+
+    /// Whether the color channels are multiplied by alpha
+    pub alpha: TextureAlpha,
+
+    /// Color space of the color channels
+    pub color_space: TextureColorSpace,
 }
 
 impl Texture {
     /// Expected bytesize of the texture data
     pub fn buffer_size(&self) -> usize {
-        self.width as usize * self.height as usize * self.format.n_channels()
+        match &self.region {
+            TextureRegion::Full => {
+                self.width as usize * self.height as usize * self.format.n_channels()
+            }
+            TextureRegion::Damaged(rects) => {
+                rects.iter().map(|rect| rect.area()).sum::<usize>() * self.format.n_channels()
+            }
+        }
+    }
+
+    /// Iterate the updated rectangles of the texture, with their (tightly packed) byte data
+    pub fn rects(&self) -> impl Iterator<Item = (TextureRect, &[u8])> {
+        let rects = match &self.region {
+            TextureRegion::Full => vec![TextureRect::new(0, 0, self.width, self.height)],
+            TextureRegion::Damaged(rects) => rects.clone(),
+        };
+
+        let n_channels = self.format.n_channels();
+        let mut offset = 0;
+
+        rects.into_iter().map(move |rect| {
+            let len = rect.area() * n_channels;
+            let data = &self.data[offset..offset + len];
+            offset += len;
+
+            (rect, data)
+        })
+    }
+
+    /// Draw `update` over this full texture, e.g. to follow a stream of damaged textures
+    ///
+    /// Returns `false`, leaving the texture as is, if the sizes or formats don't match.
+    pub fn apply(&mut self, update: &Texture) -> bool {
+        if self.region != TextureRegion::Full
+            || (self.width, self.height, &self.format)
+                != (update.width, update.height, &update.format)
+        {
+            return false;
+        }
+
+        if update.region == TextureRegion::Full {
+            self.data.clear();
+            self.data.extend_from_slice(&update.data);
+            return true;
+        }
+
+        update.copy_rects_into(&mut self.data, self.format.n_channels());
+
+        true
+    }
+
+    /// Copy the updated rectangles into `target`, the pixel data of a full frame of the same size
+    ///
+    /// `target_channels` is the channel count of the target, an RGB texture copied into RGBA
+    /// pixels is made opaque.
+    pub fn copy_rects_into(&self, target: &mut [u8], target_channels: usize) {
+        let n_channels = self.format.n_channels();
+        let stride = self.width as usize * target_channels;
+
+        for (rect, data) in self.rects() {
+            let row_len = rect.width as usize * n_channels;
+            if row_len == 0 {
+                continue;
+            }
+
+            for (row, src) in data.chunks_exact(row_len).enumerate() {
+                let start = (rect.y as usize + row) * stride + rect.x as usize * target_channels;
+                let target_row = &mut target[start..start + rect.width as usize * target_channels];
+
+                if n_channels == target_channels {
+                    target_row.copy_from_slice(src);
+                } else {
+                    for (pixel, src) in target_row
+                        .chunks_exact_mut(target_channels)
+                        .zip(src.chunks_exact(n_channels))
+                    {
+                        pixel[..n_channels].copy_from_slice(src);
+                        pixel[n_channels..].fill(255);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Convert premultiplied alpha into straight alpha, in place
+    pub fn unpremultiply(&mut self) {
+        if self.alpha == TextureAlpha::Straight {
+            return;
+        }
+
+        if self.format == TextureFormat::Rgba8 {
+            for pixel in self.data.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u32;
+                if alpha == 0 || alpha == 255 {
+                    continue;
+                }
+
+                for channel in &mut pixel[..3] {
+                    *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+                }
+            }
+        }
+
+        self.alpha = TextureAlpha::Straight;
+    }
+
+    /// Encode a full texture (e.g. a snapshot) as PNG
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        if self.region != TextureRegion::Full {
+            return Err(Error::PartialTexture);
+        }
+
+        // PNG has straight alpha
+        if self.alpha == TextureAlpha::Premultiplied {
+            let mut texture = self.clone();
+            texture.unpremultiply();
+            return texture.encode_png();
+        }
+
+        let mut png = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(match self.format {
+            TextureFormat::Rgb8 => png::ColorType::RGB,
+            TextureFormat::Rgba8 => png::ColorType::RGBA,
+        });
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.data)?;
+
+        Ok(png)
+    }
+}
+
+/// Alpha representation of a [`Texture`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureAlpha {
+    /// Color channels are independent of alpha (e.g. GDK pixbufs)
+    Straight,
+
+    /// Color channels are multiplied by alpha (e.g. cairo surfaces)
+    Premultiplied,
+}
+
+/// Color space of a [`Texture`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureColorSpace {
+    /// sRGB encoded, as rendered by web engines
+    Srgb,
+
+    /// Linear
+    Linear,
+}
+
+/// Area of the page captured by [`crate::EngineWebview::snapshot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotRegion {
+    /// The visible viewport
+    Visible,
+
+    /// The whole document, including the parts scrolled out of view
+    FullDocument,
+}
+
+/// Part of a [`Texture`] covered by its data
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextureRegion {
+    /// The whole texture
+    Full,
+
+    /// Only the damaged rectangles, everything else is unchanged since the previous texture. Data
+    /// of the rectangles is concatenated in order
+    Damaged(Vec<TextureRect>),
+}
+
+/// Rectangular area of a texture, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextureRect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Number of pixels in the rectangle
+    pub fn area(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    /// Smallest rectangle containing both rectangles
+    pub fn union(&self, other: &TextureRect) -> TextureRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+
+        TextureRect::new(
+            x,
+            y,
+            (self.x + self.width).max(other.x + other.width) - x,
+            (self.y + self.height).max(other.y + other.height) - y,
+        )
+    }
+
+    /// Whether the rectangles overlap or touch each other
+    pub fn touches(&self, other: &TextureRect) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+
+    /// Limit the rectangle within a texture of the given size
+    pub fn clamp(&self, width: u32, height: u32) -> TextureRect {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+
+        TextureRect::new(x, y, self.width.min(width - x), self.height.min(height - y))
+    }
+
+    /// Merge overlapping and touching rectangles, and drop the empty ones
+    pub fn merge(rects: &[TextureRect]) -> Vec<TextureRect> {
+        let mut merged: Vec<TextureRect> = Vec::with_capacity(rects.len());
+
+        for rect in rects.iter().filter(|rect| rect.area() > 0) {
+            let mut rect = *rect;
+
+            // a merged rectangle may touch rectangles it did not touch before
+            while let Some(index) = merged.iter().position(|other| other.touches(&rect)) {
+                rect = rect.union(&merged.swap_remove(index));
+            }
+
+            merged.push(rect);
+        }
+
+        merged
     }
 }
 
@@ -134,19 +732,41 @@ pub struct PeriodicWait {
 }
 
 /// Window size
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct WindowSize {
     /// Width of the window, in pixels
     pub width: u32,
 
     /// Height of the window, in pixels
     pub height: u32,
+
+    /// Device scale factor, i.e. how many window pixels one CSS pixel of the page covers
+    pub scale_factor: f64,
 }
 
 impl WindowSize {
     /// Construct new
     pub fn new(width: u32, height: u32) -> Self {
-        WindowSize { width, height }
+        WindowSize {
+            width,
+            height,
+            scale_factor: 1.,
+        }
+    }
+
+    /// Construct from a logical size, e.g. that of a UI node on a HiDPI display
+    pub fn from_logical(width: f32, height: f32, scale_factor: f64) -> Self {
+        WindowSize {
+            width: (width as f64 * scale_factor).round() as u32,
+            height: (height as f64 * scale_factor).round() as u32,
+            scale_factor,
+        }
+    }
+
+    /// Set the device scale factor
+    pub fn with_scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor;
+        self
     }
 }
 
@@ -155,6 +775,7 @@ impl Default for WindowSize {
         Self {
             width: 800,
             height: 600,
+            scale_factor: 1.,
         }
     }
 }
@@ -5,9 +5,13 @@
         this._promises = {};
         this._callbacks = {};
 
+        // Milliseconds to wait for the reply of a `call`, 0 waits forever
+        this.timeout = 30000;
+
         // Private internal function called on error
         this._error = (id, error) => {
             if (this._promises[id]) {
+                clearTimeout(this._promises[id].timer);
                 this._promises[id].reject(error);
                 delete this._promises[id];
             }
@@ -16,6 +20,7 @@
         // Private internal function called on result
         this._result = (id, result) => {
             if (this._promises[id]) {
+                clearTimeout(this._promises[id].timer);
                 this._promises[id].resolve(result);
                 delete this._promises[id];
             }
@@ -29,7 +34,10 @@
             const params = Array.prototype.slice.call(arguments, 1);
             const payload = { jsonrpc: "2.0", id, method, params };
             const promise = new Promise((resolve, reject) => {
-                self._promises[id] = { resolve, reject };
+                const timer = self.timeout > 0
+                    ? setTimeout(() => self._error(id, { code: -32000, message: `RPC call ${method} timed out` }), self.timeout)
+                    : undefined;
+                self._promises[id] = { resolve, reject, timer };
             });
             window.external.invoke(JSON.stringify(payload));
             return promise;
@@ -43,6 +51,13 @@
             return Promise.resolve();
         }
 
+        // Send bytes (an `ArrayBuffer` or a typed array) to a binary input event
+        this.notifyBinary = function (method, data) {
+            const payload = { jsonrpc: "2.0", method, params: [encodeBase64(data)] };
+            window.external.invoke(JSON.stringify(payload));
+            return Promise.resolve();
+        }
+
         // Register a callback
         this.on = function (method, cb) {
             if (!this._callbacks[method]) {
@@ -65,6 +80,10 @@
         // Receive a message and broadcast to callbacks
         this._message = function (method, message) {
             if (!this._callbacks[method]) {
+                // rejected notifications are reported, unless handled by `rpc.on("_error", ...)`
+                if (method === "_error") {
+                    console.error("RPC error", message);
+                }
                 return;
             }
 
@@ -72,6 +91,37 @@
                 this._callbacks[method][i](message);
             }
         }
+
+        // Receive a binary message (base64) and broadcast to callbacks as an `ArrayBuffer`
+        this._binary = function (method, data) {
+            if (this._callbacks[method]) {
+                this._message(method, decodeBase64(data));
+            }
+        }
+    }
+
+    function encodeBase64(data) {
+        const bytes = data instanceof ArrayBuffer
+            ? new Uint8Array(data)
+            : new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+
+        // `String.fromCharCode` takes a limited count of arguments
+        let binary = "";
+        for (let i = 0; i < bytes.length; i += 0x8000) {
+            binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+        }
+
+        return btoa(binary);
+    }
+
+    function decodeBase64(data) {
+        const binary = atob(data);
+        const bytes = new Uint8Array(binary.length);
+        for (let i = 0; i < binary.length; i++) {
+            bytes[i] = binary.charCodeAt(i);
+        }
+
+        return bytes.buffer;
     }
 
     window.external = window.external || {};
@@ -79,4 +129,49 @@
     window.rpc = window.external.rpc;
 
     window.rpc.notify("_webview", { initialize: null })
+
+    // Output events are buffered by the host while the next page loads. A page restored from the
+    // back/forward cache does not run this script again, so initialize again
+    window.addEventListener("pagehide", () => window.rpc.notify("_webview", { unload: null }));
+    window.addEventListener("pageshow", (event) => {
+        if (event.persisted) {
+            window.rpc.notify("_webview", { initialize: null });
+        }
+    });
+
+    // Report whether an editable element has the focus, so that the host may suppress hotkeys
+    let editableFocus = false;
+
+    function isEditable(element) {
+        if (!element) {
+            return false;
+        }
+
+        if (element.isContentEditable) {
+            return true;
+        }
+
+        switch (element.tagName) {
+            case "TEXTAREA":
+            case "SELECT":
+                return !element.disabled;
+            case "INPUT":
+                const nonTextTypes = ["button", "checkbox", "color", "file", "hidden", "image", "radio", "range", "reset", "submit"];
+                return !element.disabled && !element.readOnly && !nonTextTypes.includes(element.type);
+            default:
+                return false;
+        }
+    }
+
+    function updateEditableFocus() {
+        const editable = isEditable(document.activeElement);
+        if (editable !== editableFocus) {
+            editableFocus = editable;
+            window.rpc.notify("_webview", { editablefocus: editable });
+        }
+    }
+
+    document.addEventListener("focusin", updateEditableFocus);
+    // activeElement is updated after focusout has been dispatched
+    document.addEventListener("focusout", () => setTimeout(updateEditableFocus, 0));
 })();
@@ -0,0 +1,122 @@
+(function () {
+    const FOCUSABLE = [
+        "a[href]",
+        "area[href]",
+        "button",
+        "input",
+        "select",
+        "textarea",
+        "iframe",
+        "[tabindex]",
+        "[contenteditable]",
+    ].join(",");
+
+    function isFocusable(element) {
+        if (element.disabled || element.tabIndex < 0) {
+            return false;
+        }
+
+        if (element.tagName === "INPUT" && element.type === "hidden") {
+            return false;
+        }
+
+        const rect = element.getBoundingClientRect();
+        if (rect.width === 0 || rect.height === 0) {
+            return false;
+        }
+
+        const style = window.getComputedStyle(element);
+        return style.visibility !== "hidden" && style.display !== "none";
+    }
+
+    function focusableElements() {
+        return Array.prototype.filter.call(document.querySelectorAll(FOCUSABLE), isFocusable);
+    }
+
+    function center(rect) {
+        return { x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 };
+    }
+
+    function focus(element) {
+        element.focus();
+        element.scrollIntoView({ block: "nearest", inline: "nearest" });
+    }
+
+    function SpatialNavigation() {
+        // Move the focus to the nearest focusable element in a direction: "up", "down", "left" or "right"
+        this.move = function (direction) {
+            const elements = focusableElements();
+            const current = document.activeElement;
+
+            if (!current || current === document.body || elements.indexOf(current) === -1) {
+                // nothing focused yet, start from the top-left
+                const first = elements.sort((a, b) => {
+                    const ra = a.getBoundingClientRect();
+                    const rb = b.getBoundingClientRect();
+                    return ra.top - rb.top || ra.left - rb.left;
+                })[0];
+
+                if (first) {
+                    focus(first);
+                }
+                return;
+            }
+
+            const from = center(current.getBoundingClientRect());
+            let best = null;
+            let bestScore = Infinity;
+
+            for (const element of elements) {
+                if (element === current) {
+                    continue;
+                }
+
+                const to = center(element.getBoundingClientRect());
+                const dx = to.x - from.x;
+                const dy = to.y - from.y;
+
+                // distance along the direction, and away from it
+                let along, across;
+                switch (direction) {
+                    case "up": along = -dy; across = dx; break;
+                    case "down": along = dy; across = dx; break;
+                    case "left": along = -dx; across = dy; break;
+                    case "right": along = dx; across = dy; break;
+                    default: return;
+                }
+
+                if (along <= 0) {
+                    continue;
+                }
+
+                // prefer elements that are aligned with the current one
+                const score = along + 2 * Math.abs(across);
+                if (score < bestScore) {
+                    bestScore = score;
+                    best = element;
+                }
+            }
+
+            if (best) {
+                focus(best);
+            }
+        }
+
+        // Activate the focused element, as if it was clicked
+        this.activate = function () {
+            const current = document.activeElement;
+            if (current && current !== document.body) {
+                current.click();
+            }
+        }
+
+        // Dispatch a cancelable `navigateback` event from the focused element, pages may listen to it
+        // for closing menus and dialogs
+        this.back = function () {
+            const target = document.activeElement || document.body;
+            target.dispatchEvent(new CustomEvent("navigateback", { bubbles: true, cancelable: true }));
+        }
+    }
+
+    window.spatialNavigation = new SpatialNavigation();
+})();
@@ -8,7 +8,10 @@ use url::Url;
 
 // :set diffopt+=iwhite
 use crate::http::{Request as HttpRequest, Response as HttpResponse};
-use crate::types::{KeyboardInput, MouseEvent, Texture, TickMode, Vec2, WindowSize};
+use crate::types::{
+    CursorIcon, KeyboardInput, MouseEvent, MouseMotion, ScrollEvent, SnapshotRegion, Texture,
+    TickMode, TouchEvent, WindowSize,
+};
 use crate::window::HeadlessWindow;
 use crate::{Error, Result};
 
@@ -71,11 +74,17 @@ pub struct WebViewAttributes<T: HeadlessWindow> {
     /// Both functions return promises but `notify()` resolves immediately.
     pub rpc_handler: Option<Box<dyn Fn(&T, RpcRequest) -> Option<RpcResponse>>>,
 
+    /// Called when the page requests a different mouse cursor icon, e.g. when hovering a link
+    pub cursor_handler: Option<Box<dyn Fn(CursorIcon)>>,
+
     /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
     ///
     /// macOS doesn't provide such method and is always enabled by default. But you still need to add menu
     /// item accelerators to use shortcuts.
     pub clipboard: bool,
+
+    /// Inject `window.spatialNavigation`, for moving the focus without a pointer
+    pub spatial_navigation: bool,
 }
 
 impl<T: HeadlessWindow> Default for WebViewAttributes<T> {
@@ -88,7 +97,9 @@ impl<T: HeadlessWindow> Default for WebViewAttributes<T> {
             initialization_scripts: vec![],
             custom_protocols: vec![],
             rpc_handler: None,
+            cursor_handler: None,
             clipboard: false,
+            spatial_navigation: false,
         }
     }
 }
@@ -180,6 +191,21 @@ where
         self
     }
 
+    /// Set the handler for mouse cursor icon changes requested by the page.
+    pub fn with_cursor_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(CursorIcon) + 'static,
+    {
+        self.webview.cursor_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Expose `window.spatialNavigation` to the page, for moving the focus without a pointer.
+    pub fn with_spatial_navigation(mut self, spatial_navigation: bool) -> Self {
+        self.webview.spatial_navigation = spatial_navigation;
+        self
+    }
+
     /// Load the provided URL when the builder calling [`WebViewBuilder::build`] to create the
     /// [`WebView`]. The provided URL must be valid.
     pub fn with_url(mut self, url: &str) -> Result<Self> {
@@ -233,6 +259,13 @@ where
                 .initialization_scripts
                 .push(include_str!("javascript/rpc.js").to_string());
         }
+
+        if self.webview.spatial_navigation {
+            self.webview
+                .initialization_scripts
+                .push(include_str!("javascript/spatial_navigation.js").to_string());
+        }
+
         Ok(T::Webview::new(
             Rc::new(self.window),
             self.webview,
@@ -273,9 +306,32 @@ pub trait EngineWebview {
 
     fn version(&self) -> Result<String>;
     fn send_keyboard_input(&self, keyboard_input: KeyboardInput);
-    fn send_mouse_position(&self, position: Vec2);
+
+    /// Insert text into the currently focused element, as if it was typed by the user.
+    /// Used for characters that can not be expressed as key presses (accents, CJK, emoji, IME
+    /// commits)
+    fn send_text_input(&self, text: &str);
+    fn send_mouse_motion(&self, mouse_motion: MouseMotion);
     fn send_mouse_event(&self, mouse_event: MouseEvent);
+    fn send_scroll(&self, scroll_event: ScrollEvent);
+    fn send_touch_event(&self, touch_event: TouchEvent);
+
+    /// Give (`true`) or take away (`false`) the keyboard focus of the webview
+    fn set_focus(&self, focused: bool);
     fn get_texture(&mut self) -> Result<Option<Texture>>;
+
+    /// Same as [`EngineWebview::get_texture`], but the texture data is written into `buffer`,
+    /// reusing its allocation. The buffer is moved into the returned texture, and left untouched
+    /// if there is no new texture
+    fn get_texture_into(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Texture>> {
+        let _ = buffer;
+        self.get_texture()
+    }
+
+    /// Capture the page into a full texture, blocking until it is rendered. Unlike
+    /// [`EngineWebview::get_texture`], a texture is returned also when nothing has changed
+    fn snapshot(&mut self, region: SnapshotRegion) -> Result<Texture>;
+
     fn tick_once(&mut self);
 
     fn tick(&mut self, tick_mode: TickMode) {
@@ -325,25 +381,10 @@ pub fn rpc_proxy<T: HeadlessWindow>(
     let req = serde_json::from_str::<RpcRequest>(&js)
         .map_err(|e| Error::RpcScriptError(e.to_string(), js))?;
 
-    let mut response = (handler)(window, req);
     // Got a synchronous response so convert it to a script to be evaluated
-    if let Some(mut response) = response.take() {
-        if let Some(id) = response.id {
-            let js = if let Some(error) = response.error.take() {
-                RpcResponse::get_error_script(id, error)?
-            } else if let Some(result) = response.result.take() {
-                RpcResponse::get_result_script(id, result)?
-            } else {
-                // No error or result, assume a positive response
-                // with empty result (ACK)
-                RpcResponse::get_result_script(id, Value::Null)?
-            };
-            Ok(Some(js))
-        } else {
-            Ok(None)
-        }
-    } else {
-        Ok(None)
+    match (handler)(window, req) {
+        Some(response) => response.into_script(),
+        None => Ok(None),
     }
 }
 
@@ -361,6 +402,42 @@ pub struct RpcRequest {
     pub params: Option<Value>,
 }
 
+impl RpcRequest {
+    /// Bytes sent by `window.rpc.notifyBinary`, the base64-encoded first param.
+    pub fn binary_params(&self) -> Result<Vec<u8>> {
+        let data = self
+            .params
+            .as_ref()
+            .and_then(|params| params.get(0))
+            .and_then(Value::as_str)
+            .ok_or(Error::BinaryParams)?;
+
+        Ok(base64::decode(data)?)
+    }
+}
+
+/// Get a script that dispatches an output event to the `window.rpc.on` listeners, the payload
+/// being serialized JSON.
+pub fn output_event_script(method: &str, payload: &str) -> String {
+    format!(
+        "window.external.rpc._message({:?}, JSON.parse({:?}));",
+        method, payload
+    )
+}
+
+/// Get a script that dispatches a binary output event to the `window.rpc.on` listeners, which
+/// receive an `ArrayBuffer`.
+///
+/// The bytes are passed base64-encoded: 4 characters per 3 bytes, where a JSON array of numbers
+/// takes up to 4 characters per byte and is parsed number by number.
+pub fn binary_output_event_script(method: &str, payload: &[u8]) -> String {
+    format!(
+        "window.external.rpc._binary({:?}, \"{}\");",
+        method,
+        base64::encode(payload)
+    )
+}
+
 /// RPC response message which being sent back to the Javascript side.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RpcResponse {
@@ -391,6 +468,26 @@ impl RpcResponse {
         }
     }
 
+    /// Get a script that settles the promise of the request, `None` for notifications (no `id`).
+    pub fn into_script(mut self) -> Result<Option<String>> {
+        let id = match self.id.take() {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let js = if let Some(error) = self.error.take() {
+            RpcResponse::get_error_script(id, error)?
+        } else if let Some(result) = self.result.take() {
+            RpcResponse::get_result_script(id, result)?
+        } else {
+            // No error or result, assume a positive response
+            // with empty result (ACK)
+            RpcResponse::get_result_script(id, Value::Null)?
+        };
+
+        Ok(Some(js))
+    }
+
     /// Get a script that resolves the promise with a result.
     pub fn get_result_script(id: Value, result: Value) -> Result<String> {
         let retval = serde_json::to_string(&result)?;
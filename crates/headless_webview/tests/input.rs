@@ -0,0 +1,78 @@
+use headless_webview::prelude::*;
+
+#[test]
+pub fn test_scroll() {
+    let window = engines::dummy().build().unwrap();
+    let webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    webview.send_scroll(types::ScrollEvent {
+        delta: types::Vec2::new(0., 3.),
+        unit: types::ScrollUnit::Line,
+        position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::empty(),
+    });
+
+    webview.send_scroll(types::ScrollEvent {
+        delta: types::Vec2::new(-12.5, 0.),
+        unit: types::ScrollUnit::Pixel,
+        position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::empty(),
+    });
+
+    let scroll_events = webview.scroll_events();
+    assert_eq!(scroll_events.len(), 2);
+
+    assert_eq!(scroll_events[0].unit, types::ScrollUnit::Line);
+    assert_eq!(scroll_events[0].delta.y, 3.);
+
+    assert_eq!(scroll_events[1].unit, types::ScrollUnit::Pixel);
+    assert_eq!(scroll_events[1].delta.x, -12.5);
+}
+
+#[test]
+pub fn test_multi_touch() {
+    let window = engines::dummy().build().unwrap();
+    let webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    // two finger pinch: fingers start apart and move towards each other
+    let touches = [
+        (0, types::TouchPhase::Started, 100., 100.),
+        (1, types::TouchPhase::Started, 300., 300.),
+        (0, types::TouchPhase::Moved, 150., 150.),
+        (1, types::TouchPhase::Moved, 250., 250.),
+        (0, types::TouchPhase::Ended, 150., 150.),
+        (1, types::TouchPhase::Cancelled, 250., 250.),
+    ];
+
+    for (id, phase, x, y) in touches {
+        webview.send_touch_event(types::TouchEvent {
+            id,
+            phase,
+            position: types::Vec2::new(x, y),
+        });
+    }
+
+    let touch_events = webview.touch_events();
+    assert_eq!(touch_events.len(), touches.len());
+
+    let finger_1: Vec<_> = touch_events.iter().filter(|event| event.id == 1).collect();
+    assert_eq!(finger_1.len(), 3);
+    assert_eq!(finger_1[0].phase, types::TouchPhase::Started);
+    assert_eq!(finger_1[1].phase, types::TouchPhase::Moved);
+    assert_eq!(finger_1[1].position.x, 250.);
+    assert_eq!(finger_1[2].phase, types::TouchPhase::Cancelled);
+}
+
+#[test]
+pub fn test_focus() {
+    let window = engines::dummy().build().unwrap();
+    let webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    assert!(!webview.is_focused());
+
+    webview.set_focus(true);
+    assert!(webview.is_focused());
+
+    webview.set_focus(false);
+    assert!(!webview.is_focused());
+}
@@ -0,0 +1,65 @@
+use headless_webview::webview::{binary_output_event_script, RpcRequest, RpcResponse};
+use serde_json::json;
+
+#[test]
+pub fn test_rpc_response_script() {
+    let script = RpcResponse::new_result(Some(json!(7)), Some(json!({ "a": 1 })))
+        .into_script()
+        .unwrap();
+    assert_eq!(
+        script.as_deref(),
+        Some(r#"window.external.rpc._result(7, {"a":1})"#)
+    );
+
+    let script = RpcResponse::new_error(Some(json!(8)), Some(json!("failed")))
+        .into_script()
+        .unwrap();
+    assert_eq!(
+        script.as_deref(),
+        Some(r#"window.external.rpc._error(8, "failed")"#)
+    );
+
+    // acknowledged without a result
+    let script = RpcResponse::new_result(Some(json!(9)), None)
+        .into_script()
+        .unwrap();
+    assert_eq!(
+        script.as_deref(),
+        Some("window.external.rpc._result(9, null)")
+    );
+
+    // notifications are not answered
+    let script = RpcResponse::new_result(None, Some(json!(1)))
+        .into_script()
+        .unwrap();
+    assert_eq!(script, None);
+}
+
+#[test]
+pub fn test_binary_payload() {
+    let bytes = vec![0, 1, 2, 127, 128, 254, 255];
+
+    assert_eq!(
+        binary_output_event_script("minimap", &bytes),
+        r#"window.external.rpc._binary("minimap", "AAECf4D+/w==");"#
+    );
+
+    let request: RpcRequest = serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "method": "audio",
+        "params": ["AAECf4D+/w=="],
+    }))
+    .unwrap();
+    assert_eq!(request.binary_params().unwrap(), bytes);
+
+    // not a base64 string
+    for params in [json!([]), json!([[0, 1]]), json!(["not base64!"])] {
+        let request: RpcRequest = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "method": "audio",
+            "params": params,
+        }))
+        .unwrap();
+        assert!(request.binary_params().is_err());
+    }
+}
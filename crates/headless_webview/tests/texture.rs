@@ -15,18 +15,26 @@ pub fn test_texture() {
         button: types::MouseButton::Left,
         state: types::ElementState::Pressed,
         position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::SHIFT,
     });
 
     webview.send_mouse_event(types::MouseEvent {
         button: types::MouseButton::Left,
         state: types::ElementState::Released,
         position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::SHIFT,
     });
 
     webview.send_keyboard_input(types::KeyboardInput {
         state: types::ElementState::Pressed,
+        scan_code: 28,
+        key_code: Some(types::KeyCode::Return),
+        modifiers: types::Modifiers::SHIFT | types::Modifiers::CTRL,
+        text: None,
     });
 
+    webview.send_text_input("äö 漢字 🙂");
+
     webview.tick(types::TickMode::WaitFor(Duration::from_millis(300)));
 
     let texture = webview.get_texture().unwrap().unwrap();
@@ -39,3 +47,202 @@ pub fn test_texture() {
     assert_eq!(texture.width, 600);
     assert_eq!(texture.height, 400);
 }
+
+#[test]
+pub fn test_scale_factor() {
+    let window = engines::dummy()
+        .with_inner_size(WindowSize::from_logical(300., 200.5, 2.))
+        .build()
+        .unwrap();
+    let mut webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    assert_eq!(webview.inner_size().scale_factor, 2.);
+
+    // texture is in device pixels
+    let texture = webview.get_texture().unwrap().unwrap();
+    assert_eq!(texture.width, 600);
+    assert_eq!(texture.height, 401);
+
+    webview
+        .resize(WindowSize::from_logical(300., 200., 1.5))
+        .unwrap();
+    assert_eq!(
+        webview.inner_size(),
+        WindowSize::new(450, 300).with_scale_factor(1.5)
+    );
+}
+
+#[test]
+pub fn test_snapshot_png() {
+    let window = engines::dummy()
+        .with_inner_size(WindowSize::new(30, 20))
+        .build()
+        .unwrap();
+    let mut webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    let texture = webview
+        .snapshot(types::SnapshotRegion::FullDocument)
+        .unwrap();
+    assert_eq!((texture.width, texture.height), (30, 20));
+    assert_eq!(texture.region, types::TextureRegion::Full);
+
+    let png = texture.encode_png().unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    // damaged rectangles are not a complete image
+    let partial = types::Texture {
+        region: types::TextureRegion::Damaged(vec![types::TextureRect::new(0, 0, 1, 1)]),
+        data: vec![0; 4],
+        ..texture
+    };
+    assert!(partial.encode_png().is_err());
+}
+
+#[test]
+pub fn test_damaged_rects() {
+    let texture = types::Texture {
+        width: 4,
+        height: 4,
+        format: types::TextureFormat::Rgb8,
+        data: vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4, 5, 5, 5],
+        region: types::TextureRegion::Damaged(vec![
+            types::TextureRect::new(0, 0, 2, 2),
+            types::TextureRect::new(3, 3, 1, 1),
+        ]),
+        alpha: types::TextureAlpha::Straight,
+        color_space: types::TextureColorSpace::Srgb,
+    };
+
+    assert_eq!(texture.buffer_size(), texture.data.len());
+
+    let rects: Vec<_> = texture.rects().collect();
+    assert_eq!(rects.len(), 2);
+    assert_eq!(rects[0].0, types::TextureRect::new(0, 0, 2, 2));
+    assert_eq!(rects[0].1, &[1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4]);
+    assert_eq!(rects[1].0, types::TextureRect::new(3, 3, 1, 1));
+    assert_eq!(rects[1].1, &[5, 5, 5]);
+}
+
+#[test]
+pub fn test_apply_texture() {
+    let mut frame = types::Texture {
+        width: 3,
+        height: 2,
+        format: types::TextureFormat::Rgb8,
+        data: vec![0; 18],
+        region: types::TextureRegion::Full,
+        alpha: types::TextureAlpha::Straight,
+        color_space: types::TextureColorSpace::Srgb,
+    };
+
+    let update = types::Texture {
+        data: vec![1, 1, 1, 2, 2, 2, 3, 3, 3],
+        region: types::TextureRegion::Damaged(vec![
+            types::TextureRect::new(1, 0, 2, 1),
+            types::TextureRect::new(0, 1, 1, 1),
+        ]),
+        ..frame.clone()
+    };
+
+    assert!(frame.apply(&update));
+    assert_eq!(
+        frame.data,
+        vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3, 0, 0, 0, 0, 0, 0]
+    );
+
+    // other size
+    let resized = types::Texture {
+        width: 1,
+        height: 1,
+        data: vec![9; 3],
+        region: types::TextureRegion::Full,
+        ..update.clone()
+    };
+    assert!(!frame.apply(&resized));
+    assert_eq!(frame.data[3..6], [1, 1, 1]);
+
+    // RGB rectangles into RGBA pixels
+    let mut rgba = vec![0; 3 * 2 * 4];
+    update.copy_rects_into(&mut rgba, 4);
+    assert_eq!(
+        rgba,
+        vec![0, 0, 0, 0, 1, 1, 1, 255, 2, 2, 2, 255, 3, 3, 3, 255, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+pub fn test_unpremultiply() {
+    let mut texture = types::Texture {
+        width: 3,
+        height: 1,
+        format: types::TextureFormat::Rgba8,
+        data: vec![100, 50, 0, 200, 10, 20, 30, 0, 255, 255, 255, 255],
+        region: types::TextureRegion::Full,
+        alpha: types::TextureAlpha::Premultiplied,
+        color_space: types::TextureColorSpace::Srgb,
+    };
+
+    texture.unpremultiply();
+
+    assert_eq!(texture.alpha, types::TextureAlpha::Straight);
+    assert_eq!(
+        texture.data,
+        vec![128, 64, 0, 200, 10, 20, 30, 0, 255, 255, 255, 255]
+    );
+
+    // already straight
+    texture.unpremultiply();
+    assert_eq!(&texture.data[..4], &[128, 64, 0, 200]);
+}
+
+#[test]
+pub fn test_merge_rects() {
+    let merged = types::TextureRect::merge(&[
+        types::TextureRect::new(0, 0, 10, 10),
+        types::TextureRect::new(50, 50, 10, 10),
+        types::TextureRect::new(5, 5, 10, 10),
+        types::TextureRect::new(100, 100, 0, 10),
+    ]);
+
+    assert_eq!(
+        merged,
+        vec![
+            types::TextureRect::new(50, 50, 10, 10),
+            types::TextureRect::new(0, 0, 15, 15),
+        ]
+    );
+
+    // bridging rectangle merges the previously separate ones
+    let merged = types::TextureRect::merge(&[
+        types::TextureRect::new(0, 0, 10, 10),
+        types::TextureRect::new(20, 0, 10, 10),
+        types::TextureRect::new(10, 0, 10, 10),
+    ]);
+
+    assert_eq!(merged, vec![types::TextureRect::new(0, 0, 30, 10)]);
+
+    assert_eq!(
+        types::TextureRect::new(90, 90, 20, 20).clamp(100, 100),
+        types::TextureRect::new(90, 90, 10, 10)
+    );
+}
+
+#[test]
+pub fn test_texture_buffer_reuse() {
+    let window = engines::dummy().build().unwrap();
+    let mut webview = WebviewBuilder::new(window).unwrap().build().unwrap();
+
+    let mut buffer = Vec::new();
+    let texture = webview.get_texture_into(&mut buffer).unwrap().unwrap();
+    assert!(buffer.is_empty());
+    assert_eq!(texture.data.len(), texture.buffer_size());
+
+    // same-size frame is written into the returned buffer, without reallocating
+    let mut buffer = texture.data;
+    let (ptr, capacity) = (buffer.as_ptr(), buffer.capacity());
+
+    let texture = webview.get_texture_into(&mut buffer).unwrap().unwrap();
+    assert_eq!(texture.data.as_ptr(), ptr);
+    assert_eq!(texture.data.capacity(), capacity);
+    assert_eq!(&texture.data[..4], &[50, 180, 50, 255]);
+}
@@ -31,16 +31,22 @@ pub fn main() {
         button: types::MouseButton::Left,
         state: types::ElementState::Pressed,
         position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::empty(),
     });
 
     webview.send_mouse_event(types::MouseEvent {
         button: types::MouseButton::Left,
         state: types::ElementState::Released,
         position: types::Vec2::new(20., 30.),
+        modifiers: types::Modifiers::empty(),
     });
 
     webview.send_keyboard_input(types::KeyboardInput {
         state: types::ElementState::Pressed,
+        scan_code: 63,
+        key_code: Some(types::KeyCode::F5),
+        modifiers: types::Modifiers::empty(),
+        text: None,
     });
 
     webview.tick(types::TickMode::WaitFor(Duration::from_millis(300)));
@@ -0,0 +1,47 @@
+// Report the CSS cursor under the mouse to the script message handler `handler`, as the offscreen
+// window shows no cursor
+(function (handler) {
+    let current = null;
+
+    function isEditable(element) {
+        if (element.isContentEditable) {
+            return true;
+        }
+
+        switch (element.tagName) {
+            case "TEXTAREA":
+                return !element.disabled;
+            case "INPUT":
+                const nonTextTypes = ["button", "checkbox", "color", "file", "hidden", "image", "radio", "range", "reset", "submit"];
+                return !element.disabled && !nonTextTypes.includes(element.type);
+            default:
+                return false;
+        }
+    }
+
+    // `auto` is resolved as the browser would, for links and editable elements
+    function cursorOf(element) {
+        const cursor = getComputedStyle(element).cursor;
+        if (cursor !== "auto") {
+            return cursor;
+        }
+
+        if (element.closest("a[href], area[href]")) {
+            return "pointer";
+        }
+
+        return isEditable(element) ? "text" : "default";
+    }
+
+    document.addEventListener("mousemove", (event) => {
+        if (!(event.target instanceof Element)) {
+            return;
+        }
+
+        const cursor = cursorOf(event.target);
+        if (cursor !== current) {
+            current = cursor;
+            window.webkit.messageHandlers[handler].postMessage(cursor);
+        }
+    }, { capture: true, passive: true });
+})
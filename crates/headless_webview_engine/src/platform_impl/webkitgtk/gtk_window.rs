@@ -1,3 +1,4 @@
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
@@ -9,7 +10,7 @@ use gtk::{
     Inhibit, Orientation,
 };
 
-use headless_webview::types::WindowSize;
+use headless_webview::types::{TextureRect, WindowSize};
 use headless_webview::window::WindowId;
 use headless_webview::HeadlessWindow;
 
@@ -22,6 +23,10 @@ pub struct GtkWindow<T> {
     pub(crate) inner: T,
     pub(crate) device: Device,
     pub(crate) has_events: Rc<AtomicBool>,
+    /// Areas redrawn since the last texture
+    pub(crate) damage: Rc<RefCell<Vec<TextureRect>>>,
+    /// Device scale factor, applied as the page zoom by the webview
+    pub(crate) scale_factor: Cell<f64>,
 }
 
 // see https://gist.github.com/mertyildiran/e83fc3091b355280ada63534432adcea
@@ -39,9 +44,26 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> HeadlessWindow for GtkWindow<T>
         let has_events = Rc::new(AtomicBool::new(false));
         let window_has_events = has_events.clone();
 
-        native_window.connect_draw(move |_, _| {
+        let damage = Rc::new(RefCell::new(Vec::new()));
+        let window_damage = damage.clone();
+
+        native_window.connect_draw(move |_, cr| {
             //println!("{:?}: draw!", std::time::SystemTime::now(),);
             window_has_events.store(true, Ordering::SeqCst);
+
+            // the clip of the draw covers the redrawn area
+            if let Ok((x1, y1, x2, y2)) = cr.clip_extents() {
+                let (x1, y1) = (x1.floor().max(0.), y1.floor().max(0.));
+                let (x2, y2) = (x2.ceil().max(x1), y2.ceil().max(y1));
+
+                window_damage.borrow_mut().push(TextureRect::new(
+                    x1 as u32,
+                    y1 as u32,
+                    (x2 - x1) as u32,
+                    (y2 - y1) as u32,
+                ));
+            }
+
             Inhibit(false)
         });
 
@@ -68,7 +90,9 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> HeadlessWindow for GtkWindow<T>
             window_id: WindowId(0),
             inner: native_window,
             has_events,
+            damage,
             device,
+            scale_factor: Cell::new(inner_size.scale_factor),
         })
     }
 
@@ -79,6 +103,7 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> HeadlessWindow for GtkWindow<T>
     fn inner_size(&self) -> WindowSize {
         let gdk_window = self.inner.window().unwrap();
         WindowSize::new(gdk_window.width() as u32, gdk_window.height() as u32)
+            .with_scale_factor(self.scale_factor.get())
     }
 
     fn resize(&self, new_size: WindowSize) -> Result<()> {
@@ -93,6 +118,8 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> HeadlessWindow for GtkWindow<T>
             .unwrap()
             .resize(new_size.width as i32, new_size.height as i32);
 
+        self.scale_factor.set(new_size.scale_factor);
+
         Ok(())
     }
 }
@@ -0,0 +1,227 @@
+//! Mapping of [`KeyCode`]s and [`Modifiers`] into GDK keyvals and modifier masks
+
+use gdk::ffi;
+use headless_webview::types::{KeyCode, Modifiers};
+
+/// Convert a key code into a GDK keyval
+///
+/// Letter keys are shifted to their uppercase keyval when Shift is held, other keys
+/// are mapped to their unshifted keyval. Returns `None` for keys without a GDK counterpart.
+pub(crate) fn key_code_to_keyval(key_code: KeyCode, modifiers: Modifiers) -> Option<u32> {
+    let keyval = match key_code {
+        KeyCode::Key1 => ffi::GDK_KEY_1,
+        KeyCode::Key2 => ffi::GDK_KEY_2,
+        KeyCode::Key3 => ffi::GDK_KEY_3,
+        KeyCode::Key4 => ffi::GDK_KEY_4,
+        KeyCode::Key5 => ffi::GDK_KEY_5,
+        KeyCode::Key6 => ffi::GDK_KEY_6,
+        KeyCode::Key7 => ffi::GDK_KEY_7,
+        KeyCode::Key8 => ffi::GDK_KEY_8,
+        KeyCode::Key9 => ffi::GDK_KEY_9,
+        KeyCode::Key0 => ffi::GDK_KEY_0,
+        KeyCode::A => ffi::GDK_KEY_a,
+        KeyCode::B => ffi::GDK_KEY_b,
+        KeyCode::C => ffi::GDK_KEY_c,
+        KeyCode::D => ffi::GDK_KEY_d,
+        KeyCode::E => ffi::GDK_KEY_e,
+        KeyCode::F => ffi::GDK_KEY_f,
+        KeyCode::G => ffi::GDK_KEY_g,
+        KeyCode::H => ffi::GDK_KEY_h,
+        KeyCode::I => ffi::GDK_KEY_i,
+        KeyCode::J => ffi::GDK_KEY_j,
+        KeyCode::K => ffi::GDK_KEY_k,
+        KeyCode::L => ffi::GDK_KEY_l,
+        KeyCode::M => ffi::GDK_KEY_m,
+        KeyCode::N => ffi::GDK_KEY_n,
+        KeyCode::O => ffi::GDK_KEY_o,
+        KeyCode::P => ffi::GDK_KEY_p,
+        KeyCode::Q => ffi::GDK_KEY_q,
+        KeyCode::R => ffi::GDK_KEY_r,
+        KeyCode::S => ffi::GDK_KEY_s,
+        KeyCode::T => ffi::GDK_KEY_t,
+        KeyCode::U => ffi::GDK_KEY_u,
+        KeyCode::V => ffi::GDK_KEY_v,
+        KeyCode::W => ffi::GDK_KEY_w,
+        KeyCode::X => ffi::GDK_KEY_x,
+        KeyCode::Y => ffi::GDK_KEY_y,
+        KeyCode::Z => ffi::GDK_KEY_z,
+        KeyCode::Escape => ffi::GDK_KEY_Escape,
+        KeyCode::F1 => ffi::GDK_KEY_F1,
+        KeyCode::F2 => ffi::GDK_KEY_F2,
+        KeyCode::F3 => ffi::GDK_KEY_F3,
+        KeyCode::F4 => ffi::GDK_KEY_F4,
+        KeyCode::F5 => ffi::GDK_KEY_F5,
+        KeyCode::F6 => ffi::GDK_KEY_F6,
+        KeyCode::F7 => ffi::GDK_KEY_F7,
+        KeyCode::F8 => ffi::GDK_KEY_F8,
+        KeyCode::F9 => ffi::GDK_KEY_F9,
+        KeyCode::F10 => ffi::GDK_KEY_F10,
+        KeyCode::F11 => ffi::GDK_KEY_F11,
+        KeyCode::F12 => ffi::GDK_KEY_F12,
+        KeyCode::F13 => ffi::GDK_KEY_F13,
+        KeyCode::F14 => ffi::GDK_KEY_F14,
+        KeyCode::F15 => ffi::GDK_KEY_F15,
+        KeyCode::F16 => ffi::GDK_KEY_F16,
+        KeyCode::F17 => ffi::GDK_KEY_F17,
+        KeyCode::F18 => ffi::GDK_KEY_F18,
+        KeyCode::F19 => ffi::GDK_KEY_F19,
+        KeyCode::F20 => ffi::GDK_KEY_F20,
+        KeyCode::F21 => ffi::GDK_KEY_F21,
+        KeyCode::F22 => ffi::GDK_KEY_F22,
+        KeyCode::F23 => ffi::GDK_KEY_F23,
+        KeyCode::F24 => ffi::GDK_KEY_F24,
+        KeyCode::Snapshot => ffi::GDK_KEY_Print,
+        KeyCode::Scroll => ffi::GDK_KEY_Scroll_Lock,
+        KeyCode::Pause => ffi::GDK_KEY_Pause,
+        KeyCode::Insert => ffi::GDK_KEY_Insert,
+        KeyCode::Home => ffi::GDK_KEY_Home,
+        KeyCode::Delete => ffi::GDK_KEY_Delete,
+        KeyCode::End => ffi::GDK_KEY_End,
+        KeyCode::PageDown => ffi::GDK_KEY_Page_Down,
+        KeyCode::PageUp => ffi::GDK_KEY_Page_Up,
+        KeyCode::Left => ffi::GDK_KEY_Left,
+        KeyCode::Up => ffi::GDK_KEY_Up,
+        KeyCode::Right => ffi::GDK_KEY_Right,
+        KeyCode::Down => ffi::GDK_KEY_Down,
+        KeyCode::Back => ffi::GDK_KEY_BackSpace,
+        KeyCode::Return => ffi::GDK_KEY_Return,
+        KeyCode::Space => ffi::GDK_KEY_space,
+        KeyCode::Compose => ffi::GDK_KEY_Multi_key,
+        KeyCode::Caret => ffi::GDK_KEY_asciicircum,
+        KeyCode::Numlock => ffi::GDK_KEY_Num_Lock,
+        KeyCode::Numpad0 => ffi::GDK_KEY_KP_0,
+        KeyCode::Numpad1 => ffi::GDK_KEY_KP_1,
+        KeyCode::Numpad2 => ffi::GDK_KEY_KP_2,
+        KeyCode::Numpad3 => ffi::GDK_KEY_KP_3,
+        KeyCode::Numpad4 => ffi::GDK_KEY_KP_4,
+        KeyCode::Numpad5 => ffi::GDK_KEY_KP_5,
+        KeyCode::Numpad6 => ffi::GDK_KEY_KP_6,
+        KeyCode::Numpad7 => ffi::GDK_KEY_KP_7,
+        KeyCode::Numpad8 => ffi::GDK_KEY_KP_8,
+        KeyCode::Numpad9 => ffi::GDK_KEY_KP_9,
+        KeyCode::NumpadAdd => ffi::GDK_KEY_KP_Add,
+        KeyCode::Apostrophe => ffi::GDK_KEY_apostrophe,
+        KeyCode::Apps => ffi::GDK_KEY_Menu,
+        KeyCode::Asterisk => ffi::GDK_KEY_asterisk,
+        KeyCode::Plus => ffi::GDK_KEY_plus,
+        KeyCode::At => ffi::GDK_KEY_at,
+        KeyCode::Backslash => ffi::GDK_KEY_backslash,
+        KeyCode::Calculator => ffi::GDK_KEY_Calculator,
+        KeyCode::Capital => ffi::GDK_KEY_Caps_Lock,
+        KeyCode::Colon => ffi::GDK_KEY_colon,
+        KeyCode::Comma => ffi::GDK_KEY_comma,
+        KeyCode::Convert => ffi::GDK_KEY_Henkan,
+        KeyCode::NumpadDecimal => ffi::GDK_KEY_KP_Decimal,
+        KeyCode::NumpadDivide => ffi::GDK_KEY_KP_Divide,
+        KeyCode::Equals => ffi::GDK_KEY_equal,
+        KeyCode::Grave => ffi::GDK_KEY_grave,
+        KeyCode::Kana => ffi::GDK_KEY_Hiragana_Katakana,
+        KeyCode::Kanji => ffi::GDK_KEY_Kanji,
+        KeyCode::LAlt => ffi::GDK_KEY_Alt_L,
+        KeyCode::LBracket => ffi::GDK_KEY_bracketleft,
+        KeyCode::LControl => ffi::GDK_KEY_Control_L,
+        KeyCode::LShift => ffi::GDK_KEY_Shift_L,
+        KeyCode::LWin => ffi::GDK_KEY_Super_L,
+        KeyCode::Mail => ffi::GDK_KEY_Mail,
+        KeyCode::MediaSelect => ffi::GDK_KEY_AudioMedia,
+        KeyCode::MediaStop => ffi::GDK_KEY_AudioStop,
+        KeyCode::Minus => ffi::GDK_KEY_minus,
+        KeyCode::NumpadMultiply => ffi::GDK_KEY_KP_Multiply,
+        KeyCode::Mute => ffi::GDK_KEY_AudioMute,
+        KeyCode::MyComputer => ffi::GDK_KEY_MyComputer,
+        KeyCode::NavigateForward => ffi::GDK_KEY_Forward,
+        KeyCode::NavigateBackward => ffi::GDK_KEY_Back,
+        KeyCode::NextTrack => ffi::GDK_KEY_AudioNext,
+        KeyCode::NoConvert => ffi::GDK_KEY_Muhenkan,
+        KeyCode::NumpadComma => ffi::GDK_KEY_KP_Separator,
+        KeyCode::NumpadEnter => ffi::GDK_KEY_KP_Enter,
+        KeyCode::NumpadEquals => ffi::GDK_KEY_KP_Equal,
+        KeyCode::Oem102 => ffi::GDK_KEY_less,
+        KeyCode::Period => ffi::GDK_KEY_period,
+        KeyCode::PlayPause => ffi::GDK_KEY_AudioPlay,
+        KeyCode::Power => ffi::GDK_KEY_PowerOff,
+        KeyCode::PrevTrack => ffi::GDK_KEY_AudioPrev,
+        KeyCode::RAlt => ffi::GDK_KEY_Alt_R,
+        KeyCode::RBracket => ffi::GDK_KEY_bracketright,
+        KeyCode::RControl => ffi::GDK_KEY_Control_R,
+        KeyCode::RShift => ffi::GDK_KEY_Shift_R,
+        KeyCode::RWin => ffi::GDK_KEY_Super_R,
+        KeyCode::Semicolon => ffi::GDK_KEY_semicolon,
+        KeyCode::Slash => ffi::GDK_KEY_slash,
+        KeyCode::Sleep => ffi::GDK_KEY_Sleep,
+        KeyCode::Stop => ffi::GDK_KEY_Stop,
+        KeyCode::NumpadSubtract => ffi::GDK_KEY_KP_Subtract,
+        KeyCode::Sysrq => ffi::GDK_KEY_Sys_Req,
+        KeyCode::Tab => ffi::GDK_KEY_Tab,
+        KeyCode::Underline => ffi::GDK_KEY_underscore,
+        KeyCode::VolumeDown => ffi::GDK_KEY_AudioLowerVolume,
+        KeyCode::VolumeUp => ffi::GDK_KEY_AudioRaiseVolume,
+        KeyCode::Wake => ffi::GDK_KEY_WakeUp,
+        KeyCode::WebBack => ffi::GDK_KEY_Back,
+        KeyCode::WebFavorites => ffi::GDK_KEY_Favorites,
+        KeyCode::WebForward => ffi::GDK_KEY_Forward,
+        KeyCode::WebHome => ffi::GDK_KEY_HomePage,
+        KeyCode::WebRefresh => ffi::GDK_KEY_Refresh,
+        KeyCode::WebSearch => ffi::GDK_KEY_Search,
+        KeyCode::WebStop => ffi::GDK_KEY_Stop,
+        KeyCode::Yen => ffi::GDK_KEY_yen,
+        KeyCode::Copy => ffi::GDK_KEY_Copy,
+        KeyCode::Paste => ffi::GDK_KEY_Paste,
+        KeyCode::Cut => ffi::GDK_KEY_Cut,
+        KeyCode::AbntC1 | KeyCode::AbntC2 | KeyCode::Ax | KeyCode::Unlabeled => return None,
+    };
+
+    let keyval = keyval as u32;
+
+    if !modifiers.contains(Modifiers::SHIFT) {
+        return Some(keyval);
+    }
+
+    Some(match key_code {
+        KeyCode::Tab => ffi::GDK_KEY_ISO_Left_Tab as u32,
+        _ if is_letter(key_code) => unsafe { ffi::gdk_keyval_to_upper(keyval) },
+        _ => keyval,
+    })
+}
+
+/// Whether the key is one of the modifier keys
+pub(crate) fn is_modifier(key_code: KeyCode) -> bool {
+    matches!(
+        key_code,
+        KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LControl
+            | KeyCode::RControl
+            | KeyCode::LAlt
+            | KeyCode::RAlt
+            | KeyCode::LWin
+            | KeyCode::RWin
+    )
+}
+
+/// Convert modifiers into a `GdkModifierType` mask
+pub(crate) fn modifiers_to_state(modifiers: Modifiers) -> ffi::GdkModifierType {
+    let mut state = 0;
+
+    if modifiers.contains(Modifiers::SHIFT) {
+        state |= ffi::GDK_SHIFT_MASK;
+    }
+
+    if modifiers.contains(Modifiers::CTRL) {
+        state |= ffi::GDK_CONTROL_MASK;
+    }
+
+    if modifiers.contains(Modifiers::ALT) {
+        state |= ffi::GDK_MOD1_MASK;
+    }
+
+    if modifiers.contains(Modifiers::LOGO) {
+        state |= ffi::GDK_SUPER_MASK | ffi::GDK_MOD4_MASK;
+    }
+
+    state
+}
+
+fn is_letter(key_code: KeyCode) -> bool {
+    (KeyCode::A..=KeyCode::Z).contains(&key_code)
+}
@@ -1,40 +1,49 @@
+use std::cell::Cell;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 use std::sync::atomic::AtomicI32;
 use std::sync::atomic::Ordering;
-use std::sync::{Mutex, Once};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
 
-use gdk::gio::Cancellable;
+use gdk::gio::{prelude::CancellableExt, Cancellable};
 use gdk::{
     ffi,
     glib::{translate::ToGlibPtr, Bytes},
     prelude::{Cast, WindowExtManual},
     EventButton, EventKey, EventMask, FromEvent,
 };
-use gdk::{EventMotion, WindowHints};
+use gdk::{gdk_pixbuf::Pixbuf, EventFocus, EventMotion, EventScroll, EventTouch, WindowHints};
 
 use gtk::{
     prelude::{ContainerExt, WidgetExt, WidgetExtManual},
     traits::{BoxExt, GtkWindowExt},
 };
 
-use headless_webview::types::{Vec2, WindowSize};
+use headless_webview::types::WindowSize;
 use headless_webview::webview::rpc_proxy;
 use headless_webview::webview::web_context::WebContext;
 use headless_webview::{Error, HeadlessWindow};
 
 use webkit2gtk::traits::{SettingsExt, UserContentManagerExt, WebViewExt as webkit2gtkWebViewExt};
-use webkit2gtk::{LoadEvent, UserContentInjectedFrames, UserScript, UserScriptInjectionTime};
+use webkit2gtk::{
+    LoadEvent, SnapshotOptions, UserContentInjectedFrames, UserScript, UserScriptInjectionTime,
+};
 
 use headless_webview::{
-    types::{ElementState, KeyboardInput, MouseButton, MouseEvent, Texture, TextureFormat},
+    types::{
+        CursorIcon, ElementState, KeyboardInput, MouseButton, MouseEvent, MouseMotion, ScrollEvent,
+        ScrollUnit, SnapshotRegion, Texture, TextureAlpha, TextureColorSpace, TextureFormat,
+        TextureRect, TextureRegion, TouchEvent, TouchPhase,
+    },
     webview::{EngineWebview, WebViewAttributes},
     window::{WindowAttributes, WindowBuilder},
     Result,
 };
 
 mod gtk_window;
+mod keymap;
 mod web_context;
 use web_context::GdkWebContext;
 
@@ -42,6 +51,22 @@ use self::gtk_window::GtkWindow;
 
 static INIT: Once = Once::new();
 
+/// Amount of pixels WebKit scrolls per smooth scroll delta of 1.0
+const PIXELS_PER_SCROLL_LINE: f64 = 40.;
+
+/// More damaged rectangles than this are sent as a full texture
+const MAX_DAMAGED_RECTS: usize = 16;
+
+/// Textures are read through pixbufs: GDK unpremultiplies the (premultiplied) cairo surfaces
+/// when reading them into a pixbuf, so the engine never emits premultiplied textures
+const PIXBUF_ALPHA: TextureAlpha = TextureAlpha::Straight;
+
+/// GDK windows and WebKit snapshots are not color managed, the pixels are sRGB as rendered
+const PIXBUF_COLOR_SPACE: TextureColorSpace = TextureColorSpace::Srgb;
+
+/// A snapshot not rendered within this time is cancelled
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn init() {
     INIT.call_once(|| {
         gtk::init().unwrap();
@@ -124,6 +149,8 @@ pub struct GtkWebview<T: ContainerExt + WidgetExt + GtkWindowExt> {
     load_state: Rc<AtomicI32>, // castable to LoadState
     web_context: Rc<Mutex<WebContext<GdkWebContext>>>,
     is_visible: bool,
+    /// Size of the last texture, damaged rectangles are sent only while the size stays the same
+    texture_size: Option<(u32, u32)>,
 }
 
 impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T> {
@@ -174,7 +201,7 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         let manager = web_context.os.manager();
 
         // Connect before registering as recommended by the docs
-        manager.connect_script_message_received(None, move |_m, msg| {
+        manager.connect_script_message_received(Some(&window_hash), move |_m, msg| {
             if let Some(js) = msg.js_value() {
                 if let Some(rpc_handler) = &rpc_handler {
                     match rpc_proxy(&w, js.to_string(), rpc_handler) {
@@ -196,8 +223,11 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
 
         webview.add_events(
             EventMask::POINTER_MOTION_MASK
-                | EventMask::BUTTON1_MOTION_MASK
+                | EventMask::BUTTON_MOTION_MASK
                 | EventMask::BUTTON_PRESS_MASK
+                | EventMask::BUTTON_RELEASE_MASK
+                | EventMask::SCROLL_MASK
+                | EventMask::SMOOTH_SCROLL_MASK
                 | EventMask::TOUCH_MASK,
         );
 
@@ -219,6 +249,25 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
             );
         });
 
+        // The offscreen window has no visible cursor, so the page reports the CSS cursor under
+        // the mouse instead
+        if let Some(cursor_handler) = attributes.cursor_handler.take() {
+            let cursor_handler_name = format!("{}_cursor", window_hash);
+
+            manager.connect_script_message_received(Some(&cursor_handler_name), move |_m, msg| {
+                if let Some(js) = msg.js_value() {
+                    cursor_handler(css_cursor_icon(&js.to_string()));
+                }
+            });
+            manager.register_script_message_handler(&cursor_handler_name);
+
+            attributes.initialization_scripts.push(format!(
+                "{}({:?});",
+                include_str!("cursor.js").trim_end(),
+                cursor_handler_name
+            ));
+        }
+
         // Gtk application window can only contain one widget at a time.
         // In window, we add a GtkBox to pack menu bar. So we check if
         // there's a box widget here.
@@ -256,6 +305,10 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
             );
         }
 
+        // The offscreen window has no monitor to take the scale from, so render HiDPI content by
+        // zooming the page over the (device pixel sized) window instead
+        webview.set_zoom_level(window.scale_factor.get());
+
         // Color
         webview.set_background_color(&gdk::RGBA::new(
             attributes.color.r as f64,
@@ -297,6 +350,7 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
             load_state,
             web_context: rc_mutex_web_context,
             is_visible: true,
+            texture_size: None,
         };
 
         // Navigation
@@ -334,6 +388,23 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
             return;
         }
 
+        // the keyval of the typed character follows the keyboard layout, the input method of the
+        // webview inserts it as the text of the key
+        let keyval = match keyboard_input.text {
+            Some(ch) => Some(unsafe { ffi::gdk_unicode_to_keyval(ch as u32) }),
+            None => keyboard_input.key_code.and_then(|key_code| {
+                keymap::key_code_to_keyval(key_code, keyboard_input.modifiers)
+            }),
+        };
+
+        let keyval = match keyval {
+            Some(keyval) => keyval,
+            None => {
+                log::trace!("No keyval for keyboard input {:?}", keyboard_input);
+                return;
+            }
+        };
+
         let mut event = gdk::Event::new(match keyboard_input.state {
             ElementState::Pressed => gdk::EventType::KeyPress,
             ElementState::Released => gdk::EventType::KeyRelease,
@@ -346,18 +417,57 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         event_data.window = self.webview_window.to_glib_full(); // FIXME: safety?
         event_data.send_event = 1;
         event_data.time = ffi::GDK_CURRENT_TIME as u32;
-        event_data.state = 0; //ffi::KEYPRESS 0; // ffi::GDK_CONTROL_MASK;
-        event_data.keyval = gdk::ffi::GDK_KEY_F5 as u32; // GDK_KEY_x
-                                                         //gdk_key.length = 1; // ?
-                                                         //gdk_key.string = ??? c_char
+        event_data.state = keymap::modifiers_to_state(keyboard_input.modifiers);
+        event_data.keyval = keyval;
+
+        if let Some(ch) = keyboard_input.text {
+            let text = ch.to_string();
+            // freed along with the event
+            event_data.string = text.to_glib_full();
+            event_data.length = text.len() as i32;
+        }
 
-        event_data.hardware_keycode = 0; // ??
+        // X11 / evdev keycodes are offset by 8 from the scan code
+        event_data.hardware_keycode = (keyboard_input.scan_code + 8) as u16;
         event_data.group = 0;
-        event_data.is_modifier = 0;
+        event_data.is_modifier = keyboard_input
+            .key_code
+            .map(keymap::is_modifier)
+            .unwrap_or(false) as u32;
         event_key.put();
     }
 
-    fn send_mouse_position(&self, position: Vec2) {
+    fn send_text_input(&self, text: &str) {
+        if !self.is_visible {
+            return;
+        }
+
+        // Each character is typed as a press/release pair of its unicode keyval, which is
+        // turned back into text by the input method context of the webview
+        for ch in text.chars() {
+            let keyval = unsafe { ffi::gdk_unicode_to_keyval(ch as u32) };
+
+            for event_type in [gdk::EventType::KeyPress, gdk::EventType::KeyRelease] {
+                let mut event = gdk::Event::new(event_type);
+                event.set_device(Some(&self.window.device));
+
+                let mut event_key = <EventKey as FromEvent>::from(event).unwrap();
+                let event_data = event_key.as_mut();
+
+                event_data.window = self.webview_window.to_glib_full(); // FIXME: safety?
+                event_data.send_event = 1;
+                event_data.time = ffi::GDK_CURRENT_TIME as u32;
+                event_data.state = 0;
+                event_data.keyval = keyval;
+                event_data.hardware_keycode = 0;
+                event_data.group = 0;
+                event_data.is_modifier = 0;
+                event_key.put();
+            }
+        }
+    }
+
+    fn send_mouse_motion(&self, mouse_motion: MouseMotion) {
         if !self.is_visible {
             return;
         }
@@ -372,9 +482,14 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         event_data.time = ffi::GDK_CURRENT_TIME as u32;
 
         let area = self.webview.allocation();
-        event_data.x = area.x() as f64 + position.x as f64;
-        event_data.y = area.y() as f64 + position.y as f64;
-        event_data.state = 0;
+        event_data.x = area.x() as f64 + mouse_motion.position.x as f64;
+        event_data.y = area.y() as f64 + mouse_motion.position.y as f64;
+
+        // held buttons turn the motion into a drag (text selection, sliders, ...)
+        event_data.state = mouse_motion.buttons.iter().fold(
+            keymap::modifiers_to_state(mouse_motion.modifiers),
+            |state, button| state | button_mask(button),
+        );
 
         event_data.device = self.window.device.to_glib_full(); // FIXME: safety?
         event_motion.put();
@@ -400,13 +515,7 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         let area = self.webview.allocation();
         event_data.x = area.x() as f64 + mouse_event.position.x as f64;
         event_data.y = area.y() as f64 + mouse_event.position.y as f64;
-        event_data.state = 0; /*match mouse_event.button {
-                                  MouseButton::Left => ffi::GDK_BUTTON1_MASK,
-                                  MouseButton::Right => ffi::GDK_BUTTON2_MASK,
-                                  MouseButton::Middle => ffi::GDK_BUTTON3_MASK,
-                                  MouseButton::Other(_) => return,
-                              };
-                              */
+        event_data.state = keymap::modifiers_to_state(mouse_event.modifiers);
 
         event_data.button = match mouse_event.button {
             MouseButton::Left => 1,
@@ -418,11 +527,102 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         event_button.put();
     }
 
+    fn send_scroll(&self, scroll_event: ScrollEvent) {
+        if !self.is_visible {
+            return;
+        }
+
+        let event = gdk::Event::new(gdk::EventType::Scroll);
+
+        let mut event_scroll = <EventScroll as FromEvent>::from(event).unwrap();
+        let event_data = event_scroll.as_mut();
+
+        event_data.window = self.webview_window.to_glib_full(); // FIXME: safety?
+        event_data.send_event = 1;
+        event_data.time = ffi::GDK_CURRENT_TIME as u32;
+
+        let area = self.webview.allocation();
+        event_data.x = area.x() as f64 + scroll_event.position.x as f64;
+        event_data.y = area.y() as f64 + scroll_event.position.y as f64;
+        event_data.state = keymap::modifiers_to_state(scroll_event.modifiers);
+
+        // smooth scroll deltas are expressed in lines (wheel notches)
+        let lines_per_unit = match scroll_event.unit {
+            ScrollUnit::Line => 1.,
+            ScrollUnit::Pixel => 1. / PIXELS_PER_SCROLL_LINE,
+        };
+
+        event_data.direction = ffi::GDK_SCROLL_SMOOTH;
+        event_data.delta_x = scroll_event.delta.x as f64 * lines_per_unit;
+        event_data.delta_y = scroll_event.delta.y as f64 * lines_per_unit;
+        event_data.is_stop = 0;
+
+        event_data.device = self.window.device.to_glib_full(); // FIXME: safety?
+        event_scroll.put();
+    }
+
+    fn send_touch_event(&self, touch_event: TouchEvent) {
+        if !self.is_visible {
+            return;
+        }
+
+        let event = gdk::Event::new(match touch_event.phase {
+            TouchPhase::Started => gdk::EventType::TouchBegin,
+            TouchPhase::Moved => gdk::EventType::TouchUpdate,
+            TouchPhase::Ended => gdk::EventType::TouchEnd,
+            TouchPhase::Cancelled => gdk::EventType::TouchCancel,
+        });
+
+        let mut event_touch = <EventTouch as FromEvent>::from(event).unwrap();
+        let event_data = event_touch.as_mut();
+
+        event_data.window = self.webview_window.to_glib_full(); // FIXME: safety?
+        event_data.send_event = 1;
+        event_data.time = ffi::GDK_CURRENT_TIME as u32;
+
+        let area = self.webview.allocation();
+        event_data.x = area.x() as f64 + touch_event.position.x as f64;
+        event_data.y = area.y() as f64 + touch_event.position.y as f64;
+        event_data.state = 0;
+
+        // sequences are opaque identifiers for GDK, any non-null value unique per touch point works
+        event_data.sequence = (touch_event.id + 1) as usize as *mut ffi::GdkEventSequence;
+        event_data.emulating_pointer = 0;
+
+        event_data.device = self.window.device.to_glib_full(); // FIXME: safety?
+        event_touch.put();
+    }
+
+    fn set_focus(&self, focused: bool) {
+        if focused {
+            self.webview.grab_focus();
+        }
+
+        // the offscreen toplevel never receives focus from the window manager, so tell it
+        let toplevel_window = match self.window.inner.window() {
+            Some(toplevel_window) => toplevel_window,
+            None => return,
+        };
+
+        let event = gdk::Event::new(gdk::EventType::FocusChange);
+        let mut event_focus = <EventFocus as FromEvent>::from(event).unwrap();
+        let event_data = event_focus.as_mut();
+
+        event_data.window = toplevel_window.to_glib_full(); // FIXME: safety?
+        event_data.send_event = 1;
+        event_data.in_ = focused as i16;
+        event_focus.put();
+    }
+
     fn window(&self) -> &Self::Window {
         &self.window
     }
 
     fn get_texture(&mut self) -> Result<Option<Texture>> {
+        self.get_texture_into(&mut Vec::new())
+    }
+
+    fn get_texture_into(&mut self, buffer: &mut Vec<u8>) -> Result<Option<Texture>> {
         if !self.window().has_events.load(Ordering::SeqCst)
             || LoadState::from_i32(self.load_state.load(Ordering::SeqCst)) == LoadState::PreStart
             || !self.is_visible
@@ -431,35 +631,128 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
         }
 
         let gdk_window = self.window.inner.window().unwrap();
+        let (width, height) = (gdk_window.width() as u32, gdk_window.height() as u32);
+
+        let damage: Vec<TextureRect> = self
+            .window
+            .damage
+            .borrow_mut()
+            .drain(..)
+            .map(|rect| rect.clamp(width, height))
+            .collect();
+        let damage = TextureRect::merge(&damage);
+        let damaged_area: usize = damage.iter().map(|rect| rect.area()).sum();
+
+        // send the whole texture after resizes, and when most of it has changed anyway
+        let region = if self.texture_size != Some((width, height))
+            || damage.is_empty()
+            || damage.len() > MAX_DAMAGED_RECTS
+            || damaged_area * 2 > width as usize * height as usize
+        {
+            TextureRegion::Full
+        } else {
+            TextureRegion::Damaged(damage)
+        };
 
-        let pixbuf = gdk_window
-            .pixbuf(0, 0, gdk_window.width(), gdk_window.height())
-            .unwrap();
+        let rects = match &region {
+            TextureRegion::Full => vec![TextureRect::new(0, 0, width, height)],
+            TextureRegion::Damaged(rects) => rects.clone(),
+        };
 
-        let pixbuf_bytes: Bytes = pixbuf.read_pixel_bytes().unwrap();
+        let mut format = None;
+        buffer.clear();
+        buffer.reserve(rects.iter().map(|rect| rect.area()).sum::<usize>() * 4);
+
+        for rect in rects.iter() {
+            let pixbuf = gdk_window
+                .pixbuf(
+                    rect.x as i32,
+                    rect.y as i32,
+                    rect.width as i32,
+                    rect.height as i32,
+                )
+                .unwrap();
+
+            format = Some(match pixbuf.n_channels() {
+                3 => TextureFormat::Rgb8,
+                4 => TextureFormat::Rgba8,
+                n_channels => {
+                    return Err(Error::SnapshotError(format!(
+                        "unsupported pixbuf with {} channels",
+                        n_channels
+                    )))
+                }
+            });
 
-        let format = match pixbuf.n_channels() {
-            3 => TextureFormat::Rgb8,
-            4 => TextureFormat::Rgba8,
-            _ => todo!(), // FIXME
-        };
+            append_pixel_bytes(&pixbuf, buffer);
+        }
 
         self.window().has_events.store(false, Ordering::SeqCst);
+        self.texture_size = Some((width, height));
 
         log::trace!(
-            "Emitting texture (w={}, h={})",
-            pixbuf.width(),
-            pixbuf.height()
+            "Emitting texture (w={}, h={}, region={:?})",
+            width,
+            height,
+            region
         );
 
         Ok(Some(Texture {
-            width: pixbuf.width() as u32,
-            height: pixbuf.height() as u32,
-            format,
-            data: pixbuf_bytes.to_vec(),
+            width,
+            height,
+            format: format.unwrap_or(TextureFormat::Rgba8),
+            data: std::mem::take(buffer),
+            region,
+            alpha: PIXBUF_ALPHA,
+            color_space: PIXBUF_COLOR_SPACE,
         }))
     }
 
+    fn snapshot(&mut self, region: SnapshotRegion) -> Result<Texture> {
+        let result: Arc<Mutex<Option<Result<Texture>>>> = Default::default();
+        let callback_result = result.clone();
+
+        let cancellable = Cancellable::new();
+        self.webview.snapshot(
+            match region {
+                SnapshotRegion::Visible => webkit2gtk::SnapshotRegion::Visible,
+                SnapshotRegion::FullDocument => webkit2gtk::SnapshotRegion::FullDocument,
+            },
+            SnapshotOptions::NONE,
+            Some(&cancellable),
+            move |surface| {
+                *callback_result.lock().unwrap() = Some(snapshot_texture(surface));
+            },
+        );
+
+        // also wakes up the main loop below, if nothing else happens until the deadline
+        let timed_out = Rc::new(Cell::new(false));
+        let timeout = {
+            let timed_out = timed_out.clone();
+            gdk::glib::timeout_add_local_once(SNAPSHOT_TIMEOUT, move || {
+                timed_out.set(true);
+                cancellable.cancel();
+            })
+        };
+
+        // the snapshot is rendered asynchronously, run the main loop until it is done
+        loop {
+            if timed_out.get() {
+                return Err(Error::SnapshotError(format!(
+                    "not rendered within {:?}",
+                    SNAPSHOT_TIMEOUT
+                )));
+            }
+
+            if let Some(texture) = result.lock().unwrap().take() {
+                timeout.remove();
+                return texture;
+            }
+
+            gtk::main_iteration_do(true);
+        }
+    }
+
     fn tick_once(&mut self) {
         // TODO is it okay not to process events? or maybe reduce the interval
         if !self.is_visible {
@@ -480,6 +773,10 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> EngineWebview for GtkWebview<T>
     fn resize(&self, new_size: WindowSize) -> Result<()> {
         log::trace!("resize to {:?}", new_size);
 
+        if self.webview.zoom_level() != new_size.scale_factor {
+            self.webview.set_zoom_level(new_size.scale_factor);
+        }
+
         self.window.resize(new_size)
     }
 
@@ -516,6 +813,85 @@ impl<T: ContainerExt + WidgetExt + GtkWindowExt> Drop for GtkWebview<T> {
     }
 }
 
+/// Append the pixel bytes of the pixbuf to `data`, without the padding at the end of the rows
+fn append_pixel_bytes(pixbuf: &Pixbuf, data: &mut Vec<u8>) {
+    let bytes: Bytes = pixbuf.read_pixel_bytes().unwrap();
+    let row_len = pixbuf.width() as usize * pixbuf.n_channels() as usize;
+    let rowstride = pixbuf.rowstride() as usize;
+
+    if row_len == rowstride {
+        data.extend_from_slice(&bytes[..row_len * pixbuf.height() as usize]);
+        return;
+    }
+
+    for row in 0..pixbuf.height() as usize {
+        let start = row * rowstride;
+        data.extend_from_slice(&bytes[start..start + row_len]);
+    }
+}
+
+/// Convert a WebKit snapshot surface into a full texture
+fn snapshot_texture(
+    surface: std::result::Result<gdk::cairo::Surface, gdk::glib::Error>,
+) -> Result<Texture> {
+    let surface = surface.map_err(|e| Error::SnapshotError(e.to_string()))?;
+    let surface = gdk::cairo::ImageSurface::try_from(surface)
+        .map_err(|_| Error::SnapshotError("not an image surface".into()))?;
+
+    let pixbuf = gdk::pixbuf_get_from_surface(&surface, 0, 0, surface.width(), surface.height())
+        .ok_or_else(|| Error::SnapshotError("could not read the surface".into()))?;
+
+    let format = match pixbuf.n_channels() {
+        3 => TextureFormat::Rgb8,
+        _ => TextureFormat::Rgba8,
+    };
+
+    let mut data = Vec::with_capacity(
+        pixbuf.width() as usize * pixbuf.height() as usize * format.n_channels(),
+    );
+    append_pixel_bytes(&pixbuf, &mut data);
+
+    Ok(Texture {
+        width: pixbuf.width() as u32,
+        height: pixbuf.height() as u32,
+        format,
+        data,
+        region: TextureRegion::Full,
+        alpha: PIXBUF_ALPHA,
+        color_space: PIXBUF_COLOR_SPACE,
+    })
+}
+
+/// Cursor icon of a CSS `cursor` value, as reported by `cursor.js`
+fn css_cursor_icon(cursor: &str) -> CursorIcon {
+    // `url(...)` images are not supported, use their fallback keyword
+    match cursor.rsplit(',').next().unwrap_or_default().trim() {
+        "pointer" => CursorIcon::Hand,
+        "text" | "vertical-text" => CursorIcon::Text,
+        "crosshair" => CursorIcon::Crosshair,
+        "move" | "all-scroll" => CursorIcon::Move,
+        "wait" => CursorIcon::Wait,
+        "progress" => CursorIcon::Progress,
+        "help" => CursorIcon::Help,
+        "not-allowed" | "no-drop" => CursorIcon::NotAllowed,
+        "grab" => CursorIcon::Grab,
+        "grabbing" => CursorIcon::Grabbing,
+        _ => CursorIcon::Default,
+    }
+}
+
+/// `GdkModifierType` mask of a held mouse button
+fn button_mask(button: &MouseButton) -> ffi::GdkModifierType {
+    match button {
+        MouseButton::Left => ffi::GDK_BUTTON1_MASK,
+        MouseButton::Middle => ffi::GDK_BUTTON2_MASK,
+        MouseButton::Right => ffi::GDK_BUTTON3_MASK,
+        MouseButton::Other(4) => ffi::GDK_BUTTON4_MASK,
+        MouseButton::Other(5) => ffi::GDK_BUTTON5_MASK,
+        MouseButton::Other(_) => 0,
+    }
+}
+
 fn init_script(webview: &webkit2gtk::WebView, js: &str) -> Result<()> {
     if let Some(manager) = webview.user_content_manager() {
         let script = UserScript::new(
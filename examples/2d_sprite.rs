@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_webview::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WebviewPlugin::with_engine(webview_engine::headless))
+        .add_startup_system(setup)
+        .add_system(bobber)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+
+    // background sprite
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgb(0.3, 0.5, 0.3),
+            custom_size: Some(Vec2::new(600., 400.)),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    // webview, drawn on top of the background by its z
+    commands.spawn_bundle(WebviewSpriteBundle {
+        webview: Webview {
+            uri: Some(String::from("https://bevyengine.org/")),
+            color: Color::rgba(0.3, 0.3, 0.3, 0.5),
+            ..Default::default()
+        },
+        size: WebviewSize {
+            x: 480.,
+            y: 320.,
+            ppu: 1.,
+        },
+        transform: Transform::from_xyz(0., 0., 1.),
+        ..Default::default()
+    });
+}
+
+fn bobber(time: Res<Time>, mut query: Query<&mut Transform, With<Webview>>) {
+    for mut transform in query.iter_mut() {
+        transform.translation.y = 20. * time.seconds_since_startup().sin() as f32;
+    }
+}
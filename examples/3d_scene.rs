@@ -1,4 +1,4 @@
-use bevy::{log, prelude::*};
+use bevy::prelude::*;
 use bevy_webview::prelude::*;
 
 fn main() {
@@ -64,8 +64,6 @@ fn setup(
         },
         ..Default::default()
     });
-
-    log::error!("Note that 3D canvas interaction is not implemented yet, please provide feedback of needs at https://github.com/blaind/bevy_webview/issues/1");
 }
 
 fn rotator(time: Res<Time>, mut query: Query<&mut Transform, With<Webview>>) {
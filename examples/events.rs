@@ -11,10 +11,15 @@ fn main() {
         .add_webview_input_event::<LoginRequest>("login")
         .add_webview_input_event::<CloseRequest>("close")
         .add_webview_output_event::<AppTime>("app_time")
+        .add_webview_rpc_method::<UsernameRequest, UsernameResponse>("username_available")
+        .add_webview_binary_input_event::<Upload>("upload")
+        .add_webview_binary_output_event::<Waveform>("waveform")
         .add_startup_system(setup)
         .add_system(login_handler)
         .add_system(send_time_to_all_webviews_system)
         .add_system(close_handler)
+        .add_system(username_available_handler)
+        .add_system(upload_handler)
         .run();
 }
 
@@ -70,6 +75,60 @@ fn login_handler(mut login_request_events: WebviewEventReader<LoginRequest>) {
     }
 }
 
+#[derive(Deserialize, Debug)]
+pub struct UsernameRequest {
+    username: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UsernameResponse {
+    available: bool,
+}
+
+fn username_available_handler(mut requests: WebviewRpcRequests<UsernameRequest, UsernameResponse>) {
+    // the responder resolves the promise of `rpc.call`, and may be stored to answer later
+    for (request, responder) in requests.iter() {
+        if request.username.is_empty() {
+            responder.reject(WebviewRpcError::new("username is empty"));
+            continue;
+        }
+
+        responder.respond(UsernameResponse {
+            available: request.username != "admin",
+        });
+    }
+}
+
+/// Bytes sent by `rpc.notifyBinary("upload", ...)`
+#[derive(Debug)]
+pub struct Upload(Vec<u8>);
+
+impl From<Vec<u8>> for Upload {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+fn upload_handler(mut upload_events: WebviewEventReader<Upload>) {
+    for upload in upload_events.iter() {
+        println!(
+            "Upload of {} bytes: {:?}",
+            upload.0.len(),
+            String::from_utf8_lossy(&upload.0)
+        );
+    }
+}
+
+/// Received as an `ArrayBuffer` by `rpc.on("waveform", ...)`
+#[derive(Debug)]
+pub struct Waveform(Vec<u8>);
+
+impl AsRef<[u8]> for Waveform {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CloseRequest;
 
@@ -90,12 +149,21 @@ struct TimeTick(Timer);
 
 fn send_time_to_all_webviews_system(
     mut app_time: WebviewEventWriter<AppTime>,
+    mut waveform: WebviewEventWriter<Waveform>,
     time: Res<Time>,
     mut tick: ResMut<TimeTick>,
 ) {
     if tick.0.tick(time.delta()).just_finished() {
+        let seconds_since_startup = time.seconds_since_startup();
+
         app_time.send(AppTime {
-            seconds_since_startup: time.seconds_since_startup(),
+            seconds_since_startup,
         });
+
+        waveform.send(Waveform(
+            (0..32)
+                .map(|i| (128.0 + 127.0 * (seconds_since_startup + i as f64 / 4.0).sin()) as u8)
+                .collect(),
+        ));
     }
 }
@@ -1,18 +1,28 @@
-use std::{any::TypeId, collections::HashMap, sync::atomic::AtomicBool};
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::atomic::AtomicBool,
+};
 
 use bevy::{
     ecs::system::{Resource, SystemParam},
+    log,
     prelude::*,
 };
-use headless_webview::webview::RpcRequest;
+use crossbeam_channel::Sender;
+use headless_webview::webview::{RpcRequest, RpcResponse};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use crate::{systems, PostUpdateLabel, PreUpdateLabel};
+use crate::{
+    systems, types::WebviewAction, PostUpdateLabel, PreUpdateLabel, RPC_ERROR_OUTPUT_METHOD,
+};
 
 /// Mapping of RPC Input Event methods
 #[derive(Default)]
 pub(crate) struct InputEventMapping {
-    pub events: HashMap<TypeId, &'static str>,
+    pub events: HashMap<TypeId, (&'static str, WebviewRpcParams)>,
 }
 
 /// Mapping of RPC Output Event methods
@@ -21,6 +31,111 @@ pub(crate) struct OutputEventMapping {
     pub events: HashMap<TypeId, &'static str>,
 }
 
+/// Mapping of RPC request/response methods
+#[derive(Default)]
+pub(crate) struct RpcMethodMapping {
+    pub methods: HashMap<TypeId, (&'static str, WebviewRpcParams)>,
+}
+
+/// Mapping of binary event methods, the payloads are bytes (`ArrayBuffer` in Javascript)
+#[derive(Default)]
+pub(crate) struct BinaryEventMapping {
+    pub input_events: HashMap<TypeId, &'static str>,
+    pub output_events: HashMap<TypeId, &'static str>,
+}
+
+/// How the arguments of `window.rpc.notify` / `window.rpc.call` are deserialized into the
+/// registered type
+///
+/// Params that do not deserialize reject the call with [`WebviewRpcError::INVALID_PARAMS`]. For
+/// notifications, the error is sent to the `window.rpc.on("_error", ...)` listeners.
+///
+/// # Example
+///
+/// ```rust
+/// # use bevy_webview::serde::Deserialize;
+/// use bevy::prelude::*;
+/// use bevy_webview::prelude::*;
+///
+/// // rpc.notify("move", 1.5, 2)
+/// #[derive(Deserialize, Debug)]
+/// pub struct Move(f32, f32);
+///
+/// // rpc.notify("ping")
+/// #[derive(Deserialize, Debug)]
+/// pub struct Ping;
+///
+/// fn register(app: &mut App) {
+///     app.add_webview_input_event_with_params::<Move>("move", WebviewRpcParams::Tuple)
+///         .add_webview_input_event_with_params::<Ping>("ping", WebviewRpcParams::Unit);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WebviewRpcParams {
+    /// The first argument, `rpc.notify("login", { username: "ferris" })`
+    #[default]
+    First,
+
+    /// All the arguments as a tuple (or a tuple struct), `rpc.notify("move", 1.5, 2)`
+    Tuple,
+
+    /// Named params: the properties of a single object argument, `rpc.notify("move", { x: 1.5 })`,
+    /// or JSON-RPC params by-name
+    Named,
+
+    /// No arguments, for unit structs, `rpc.notify("ping")`
+    Unit,
+}
+
+impl WebviewRpcParams {
+    /// Deserialize the registered type from the params of a request, or an error message for JS
+    pub(crate) fn deserialize<T>(&self, params: Option<&serde_json::Value>) -> Result<T, String>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        let value = self.extract(params)?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    /// The value the registered type is deserialized from
+    fn extract(&self, params: Option<&serde_json::Value>) -> Result<serde_json::Value, String> {
+        use serde_json::Value;
+
+        // JS passes the arguments as an array, JSON-RPC clients may also omit the params
+        let arguments = match params {
+            None | Some(Value::Null) => &[][..],
+            Some(Value::Array(arguments)) => arguments.as_slice(),
+            Some(params @ Value::Object(_)) => std::slice::from_ref(params),
+            Some(other) => {
+                return Err(format!(
+                    "params must be an array or an object, got {}",
+                    other
+                ))
+            }
+        };
+
+        match (self, arguments) {
+            (WebviewRpcParams::First, arguments) => {
+                Ok(arguments.first().cloned().unwrap_or(Value::Null))
+            }
+
+            (WebviewRpcParams::Tuple, arguments) => Ok(Value::Array(arguments.to_vec())),
+
+            (WebviewRpcParams::Named, [named @ Value::Object(_)]) => Ok(named.clone()),
+            (WebviewRpcParams::Named, arguments) => Err(format!(
+                "expected a single object of named params, got {} arguments",
+                arguments.len()
+            )),
+
+            (WebviewRpcParams::Unit, []) => Ok(Value::Null),
+            (WebviewRpcParams::Unit, arguments) => Err(format!(
+                "expected no params, got {} arguments",
+                arguments.len()
+            )),
+        }
+    }
+}
+
 // RPC events from a webview
 pub(crate) struct InputEvent {
     // Sending entity
@@ -107,6 +222,176 @@ impl<'w, 's, T: Resource> WebviewEventWriter<'w, 's, T> {
     }
 }
 
+/// Request of an RPC method registered with [`WebviewApp::add_webview_rpc_method`]
+pub struct WebviewRpcRequest<Req, Resp> {
+    pub(crate) params: Req,
+    pub(crate) responder: WebviewRpcResponder<Resp>,
+}
+
+/// Read requests of `window.rpc.call` from Javascript, each with a responder that settles the
+/// promise of the call with a `Resp`
+#[derive(SystemParam)]
+pub struct WebviewRpcRequests<'w, 's, Req: Resource, Resp: 'static> {
+    pub requests: EventReader<'w, 's, WebviewRpcRequest<Req, Resp>>,
+}
+
+impl<'w, 's, Req: Resource, Resp: 'static> WebviewRpcRequests<'w, 's, Req, Resp> {
+    /// Iterate the requests. The responder may be kept and answered on a later frame
+    pub fn iter(&mut self) -> impl DoubleEndedIterator<Item = (&Req, WebviewRpcResponder<Resp>)> {
+        self.requests
+            .iter_with_id()
+            .map(|(request, _id)| (&request.params, request.responder.clone()))
+    }
+}
+
+/// Answers a single `window.rpc.call` with a `Resp`. Calls that are not answered are rejected by
+/// the Javascript side after `window.rpc.timeout` milliseconds (30 seconds by default)
+pub struct WebviewRpcResponder<Resp = ()> {
+    entity: Entity,
+    method: String,
+    id: Option<serde_json::Value>,
+    webview_action_tx: Sender<WebviewAction>,
+    response: PhantomData<fn(Resp)>,
+}
+
+// not derived, `Resp` itself does not need to be `Clone` / `Debug`
+impl<Resp> Clone for WebviewRpcResponder<Resp> {
+    fn clone(&self) -> Self {
+        Self {
+            entity: self.entity,
+            method: self.method.clone(),
+            id: self.id.clone(),
+            webview_action_tx: self.webview_action_tx.clone(),
+            response: PhantomData,
+        }
+    }
+}
+
+impl<Resp> std::fmt::Debug for WebviewRpcResponder<Resp> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebviewRpcResponder")
+            .field("entity", &self.entity)
+            .field("method", &self.method)
+            .field("id", &self.id)
+            .field("response", &type_name::<Resp>())
+            .finish()
+    }
+}
+
+impl<Resp> WebviewRpcResponder<Resp> {
+    pub(crate) fn new(
+        entity: Entity,
+        request: &RpcRequest,
+        webview_action_tx: Sender<WebviewAction>,
+    ) -> Self {
+        Self {
+            entity,
+            method: request.method.clone(),
+            id: request.id.clone(),
+            webview_action_tx,
+            response: PhantomData,
+        }
+    }
+
+    /// The calling webview
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Resolve the promise with `result`, of the response type the method was registered with
+    pub fn respond(self, result: Resp)
+    where
+        Resp: Serialize,
+    {
+        // notifications (`window.rpc.notify`) have no promise to settle
+        if self.id.is_none() {
+            return;
+        }
+
+        match serde_json::to_value(result) {
+            Ok(result) => self.send(WebviewAction::SendRpcResponse(
+                self.entity,
+                RpcResponse::new_result(self.id.clone(), Some(result)),
+            )),
+            Err(e) => self.reject(WebviewRpcError::new(e.to_string())),
+        }
+    }
+
+    /// Reject the promise with `error`
+    ///
+    /// Notifications (`window.rpc.notify`) have no promise, the error is sent to the
+    /// `window.rpc.on("_error", ...)` listeners instead, with the method in `data.method`
+    pub fn reject(self, mut error: WebviewRpcError) {
+        let action = match &self.id {
+            Some(id) => WebviewAction::SendRpcResponse(
+                self.entity,
+                // unwrap ok, the error is a plain struct
+                RpcResponse::new_error(
+                    Some(id.clone()),
+                    Some(serde_json::to_value(error).unwrap()),
+                ),
+            ),
+            None => {
+                error
+                    .data
+                    .get_or_insert_with(|| json!({ "method": self.method }));
+
+                WebviewAction::SendOutputEvent(
+                    Some(self.entity),
+                    String::from(RPC_ERROR_OUTPUT_METHOD),
+                    serde_json::to_string(&error).unwrap(),
+                )
+            }
+        };
+
+        self.send(action);
+    }
+
+    fn send(&self, action: WebviewAction) {
+        if self.webview_action_tx.send(action).is_err() {
+            log::warn!("Webview {:?} RPC response after exit", self.entity);
+        }
+    }
+}
+
+/// [JSON-RPC error](https://www.jsonrpc.org/specification#error_object), the rejection value of
+/// `window.rpc.call`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct WebviewRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl WebviewRpcError {
+    /// Application defined error
+    pub const SERVER_ERROR: i64 = -32000;
+    /// The parameters did not deserialize into the request type
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// No method or event registered with the name
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+
+    /// Application defined error, with code [`WebviewRpcError::SERVER_ERROR`]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::with_code(Self::SERVER_ERROR, message)
+    }
+
+    pub fn with_code(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Additional information about the error
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
 /// Trait that extends a Bevy [`App`] for registring webview events
 /// # Example
 ///
@@ -125,14 +410,26 @@ impl<'w, 's, T: Resource> WebviewEventWriter<'w, 's, T> {
 ///     seconds_since_startup: f64,
 /// }
 ///
+/// #[derive(Deserialize, Debug)]
+/// pub struct UserRequest {
+///     id: u32,
+/// }
+///
+/// #[derive(Serialize, Debug)]
+/// pub struct User {
+///     name: String,
+/// }
+///
 /// fn main() {
 ///     App::new()
 ///         .add_plugins(DefaultPlugins)
 ///         .add_plugin(WebviewPlugin::new().register_engine(webview_engine::headless))
 ///         .add_webview_input_event::<LoginRequest>("login")
 ///         .add_webview_output_event::<AppTime>("app_time")
+///         .add_webview_rpc_method::<UserRequest, User>("user")
 ///         .add_system(login_handler)
-///         .add_system(send_time_system);
+///         .add_system(send_time_system)
+///         .add_system(user_handler);
 ///         // .run();
 /// }
 ///
@@ -147,10 +444,35 @@ impl<'w, 's, T: Resource> WebviewEventWriter<'w, 's, T> {
 ///         seconds_since_startup: time.seconds_since_startup(),
 ///     });
 /// }
+///
+/// // called from Javascript by `await window.rpc.call("user", { id: 1 })`
+/// fn user_handler(mut requests: WebviewRpcRequests<UserRequest, User>) {
+///     for (request, responder) in requests.iter() {
+///         match request.id {
+///             1 => responder.respond(User { name: String::from("Ferris") }),
+///             _ => responder.reject(WebviewRpcError::new("unknown user")),
+///         }
+///     }
+/// }
 /// ```
 pub trait WebviewApp {
     /// Register an input webview event. `method` is an identifier key for sending messages from JS
+    ///
+    /// The event is deserialized from the first argument, see
+    /// [`WebviewApp::add_webview_input_event_with_params`] for the other argument layouts
     fn add_webview_input_event<T>(&mut self, method: &'static str) -> &mut Self
+    where
+        T: Resource + for<'de> serde::Deserialize<'de>,
+    {
+        self.add_webview_input_event_with_params::<T>(method, WebviewRpcParams::default())
+    }
+
+    /// Register an input webview event, deserialized from the JS arguments as given by `params`
+    fn add_webview_input_event_with_params<T>(
+        &mut self,
+        method: &'static str,
+        params: WebviewRpcParams,
+    ) -> &mut Self
     where
         T: Resource + for<'de> serde::Deserialize<'de>;
 
@@ -158,10 +480,48 @@ pub trait WebviewApp {
     fn add_webview_output_event<T>(&mut self, method: &'static str) -> &mut Self
     where
         T: Resource + for<'de> serde::Serialize;
+
+    /// Register an RPC method answering `window.rpc.call(method, ...)` from JS. Requests are read
+    /// with [`WebviewRpcRequests<Req, Resp>`], and answered with a `Resp`
+    fn add_webview_rpc_method<Req, Resp>(&mut self, method: &'static str) -> &mut Self
+    where
+        Req: Resource + for<'de> serde::Deserialize<'de>,
+        Resp: serde::Serialize + 'static,
+    {
+        self.add_webview_rpc_method_with_params::<Req, Resp>(method, WebviewRpcParams::default())
+    }
+
+    /// Register an RPC method, the request is deserialized from the JS arguments as given by
+    /// `params`
+    fn add_webview_rpc_method_with_params<Req, Resp>(
+        &mut self,
+        method: &'static str,
+        params: WebviewRpcParams,
+    ) -> &mut Self
+    where
+        Req: Resource + for<'de> serde::Deserialize<'de>,
+        Resp: serde::Serialize + 'static;
+
+    /// Register a binary input event, sent from JS by `window.rpc.notifyBinary(method, data)`
+    /// with an `ArrayBuffer` or a typed array. The bytes are read as a `T` with
+    /// [`WebviewEventReader<T>`]
+    fn add_webview_binary_input_event<T>(&mut self, method: &'static str) -> &mut Self
+    where
+        T: Resource + From<Vec<u8>>;
+
+    /// Register a binary output event, received by the `window.rpc.on(method, ...)` listeners as
+    /// an `ArrayBuffer` instead of JSON. Sent with [`WebviewEventWriter<T>`]
+    fn add_webview_binary_output_event<T>(&mut self, method: &'static str) -> &mut Self
+    where
+        T: Resource + AsRef<[u8]>;
 }
 
 impl WebviewApp for App {
-    fn add_webview_input_event<T>(&mut self, method: &'static str) -> &mut Self
+    fn add_webview_input_event_with_params<T>(
+        &mut self,
+        method: &'static str,
+        params: WebviewRpcParams,
+    ) -> &mut Self
     where
         T: Resource + for<'de> serde::Deserialize<'de>,
     {
@@ -170,7 +530,9 @@ impl WebviewApp for App {
             .get_resource_mut::<InputEventMapping>()
             .expect("Add `WebviewPlugin` before calling `.add_webview_input_event`");
 
-        rpc_input_events.events.insert(TypeId::of::<T>(), method);
+        rpc_input_events
+            .events
+            .insert(TypeId::of::<T>(), (method, params));
 
         self.add_event::<WebviewEvent<T>>();
 
@@ -196,7 +558,83 @@ impl WebviewApp for App {
 
         self.add_system_to_stage(
             CoreStage::PostUpdate,
-            systems::rpc_event_sender::<T>.label(PostUpdateLabel::Pre),
+            systems::rpc_event_sender::<T>
+                .label(PostUpdateLabel::Pre)
+                .after(systems::create_webview_system),
+        );
+
+        self
+    }
+
+    fn add_webview_rpc_method_with_params<Req, Resp>(
+        &mut self,
+        method: &'static str,
+        params: WebviewRpcParams,
+    ) -> &mut Self
+    where
+        Req: Resource + for<'de> serde::Deserialize<'de>,
+        Resp: serde::Serialize + 'static,
+    {
+        let mut rpc_methods = self
+            .world
+            .get_resource_mut::<RpcMethodMapping>()
+            .expect("Add `WebviewPlugin` before calling `.add_webview_rpc_method`");
+
+        rpc_methods
+            .methods
+            .insert(TypeId::of::<Req>(), (method, params));
+
+        self.add_event::<WebviewRpcRequest<Req, Resp>>();
+
+        self.add_system_to_stage(
+            CoreStage::PreUpdate,
+            systems::rpc_request_receiver::<Req, Resp>.after(PreUpdateLabel::Pre),
+        );
+
+        self
+    }
+
+    fn add_webview_binary_input_event<T>(&mut self, method: &'static str) -> &mut Self
+    where
+        T: Resource + From<Vec<u8>>,
+    {
+        let mut binary_events = self
+            .world
+            .get_resource_mut::<BinaryEventMapping>()
+            .expect("Add `WebviewPlugin` before calling `.add_webview_binary_input_event`");
+
+        binary_events.input_events.insert(TypeId::of::<T>(), method);
+
+        self.add_event::<WebviewEvent<T>>();
+
+        self.add_system_to_stage(
+            CoreStage::PreUpdate,
+            systems::rpc_binary_event_receiver::<T>.after(PreUpdateLabel::Pre),
+        );
+
+        self
+    }
+
+    fn add_webview_binary_output_event<T>(&mut self, method: &'static str) -> &mut Self
+    where
+        T: Resource + AsRef<[u8]>,
+    {
+        let mut binary_events = self
+            .world
+            .get_resource_mut::<BinaryEventMapping>()
+            .expect("Add `WebviewPlugin` before calling `.add_webview_binary_output_event`");
+
+        binary_events
+            .output_events
+            .insert(TypeId::of::<T>(), method);
+
+        self.add_event::<WebviewEvent<T>>();
+
+        self.add_system_to_stage(
+            CoreStage::PostUpdate,
+            systems::rpc_binary_event_sender::<T>
+                .label(PostUpdateLabel::Pre)
+                .after(systems::create_webview_system),
         );
 
         self
@@ -209,4 +647,8 @@ impl WebviewApp for App {
 pub(crate) enum BuiltinWebviewEvent {
     Despawn,
     Initialize,
+    /// The page is navigating away, the RPC is initialized again by the next page
+    Unload,
+    /// Whether an editable element of the page has the focus
+    EditableFocus(bool),
 }
@@ -0,0 +1,103 @@
+//! Reusable texture buffers, shared between the webview runner thread and Bevy
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use bevy::prelude::*;
+
+/// Buffers kept per webview: one being written by the runner, one in transit and one spare
+const MAX_BUFFERS_PER_WEBVIEW: usize = 3;
+
+/// Pool of webview texture buffers, available as a resource
+///
+/// The runner thread writes each webview frame into a buffer taken from the pool, and the buffer
+/// is returned after the frame has been applied to the Bevy image. Full frames of the same size
+/// are swapped with the image data instead of copied. Once warmed up, same-size frames do not
+/// allocate, which can be verified with [`WebviewFrameBuffers::stats`].
+#[derive(Clone, Default)]
+pub struct WebviewFrameBuffers {
+    inner: Arc<FrameBuffersInner>,
+}
+
+#[derive(Default)]
+struct FrameBuffersInner {
+    buffers: Mutex<HashMap<Entity, Vec<Vec<u8>>>>,
+    allocations: AtomicU64,
+    reuses: AtomicU64,
+}
+
+/// Frame buffer allocation counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameBufferStats {
+    /// Frames whose buffer had to be allocated or grown
+    pub allocations: u64,
+
+    /// Frames written into a reused buffer, without allocating
+    pub reuses: u64,
+}
+
+impl WebviewFrameBuffers {
+    /// Allocation counters since startup
+    pub fn stats(&self) -> FrameBufferStats {
+        FrameBufferStats {
+            allocations: self.inner.allocations.load(Ordering::Relaxed),
+            reuses: self.inner.reuses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Take a buffer for a frame of the webview, empty if none is available
+    pub(crate) fn take(&self, entity: Entity) -> Vec<u8> {
+        self.inner
+            .buffers
+            .lock()
+            .unwrap()
+            .get_mut(&entity)
+            .and_then(|buffers| buffers.pop())
+            .unwrap_or_default()
+    }
+
+    /// Create the pool of a launched webview
+    pub(crate) fn insert(&self, entity: Entity) {
+        self.inner
+            .buffers
+            .lock()
+            .unwrap()
+            .entry(entity)
+            .or_default();
+    }
+
+    /// Return a buffer to the pool of the webview, dropped if the webview was removed
+    pub(crate) fn recycle(&self, entity: Entity, buffer: Vec<u8>) {
+        if buffer.capacity() == 0 {
+            return;
+        }
+
+        let mut buffers = self.inner.buffers.lock().unwrap();
+        let buffers = match buffers.get_mut(&entity) {
+            Some(buffers) => buffers,
+            None => return,
+        };
+
+        if buffers.len() < MAX_BUFFERS_PER_WEBVIEW {
+            buffers.push(buffer);
+        }
+    }
+
+    /// Count a written frame, by the buffer capacity before and after writing it
+    pub(crate) fn count_frame(&self, capacity_before: usize, capacity_after: usize) {
+        if capacity_after > capacity_before {
+            self.inner.allocations.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.inner.reuses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop the buffers of a removed webview
+    pub(crate) fn remove(&self, entity: Entity) {
+        self.inner.buffers.lock().unwrap().remove(&entity);
+    }
+}
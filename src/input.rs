@@ -0,0 +1,354 @@
+//! Conversions between Bevy input types and webview engine input types
+use bevy::{
+    input::{
+        keyboard::{KeyCode, KeyboardInput},
+        ElementState,
+    },
+    prelude::*,
+};
+use headless_webview::types::{
+    CursorIcon as WebviewCursorIcon, KeyCode as WebviewKeyCode, Modifiers,
+    MouseButton as WebviewMouseButton,
+};
+
+/// Collect the currently held modifier keys
+pub(crate) fn modifiers_from_input(keyboard_input: &Input<KeyCode>) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+
+    modifiers.set(
+        Modifiers::SHIFT,
+        keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]),
+    );
+    modifiers.set(
+        Modifiers::CTRL,
+        keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]),
+    );
+    modifiers.set(
+        Modifiers::ALT,
+        keyboard_input.any_pressed([KeyCode::LAlt, KeyCode::RAlt]),
+    );
+    modifiers.set(
+        Modifiers::LOGO,
+        keyboard_input.any_pressed([KeyCode::LWin, KeyCode::RWin]),
+    );
+
+    modifiers
+}
+
+/// Convert a cursor icon requested by a webview into a Bevy cursor icon
+pub(crate) fn from_webview_cursor_icon(icon: WebviewCursorIcon) -> CursorIcon {
+    match icon {
+        WebviewCursorIcon::Default => CursorIcon::Default,
+        WebviewCursorIcon::Hand => CursorIcon::Hand,
+        WebviewCursorIcon::Text => CursorIcon::Text,
+        WebviewCursorIcon::Crosshair => CursorIcon::Crosshair,
+        WebviewCursorIcon::Move => CursorIcon::Move,
+        WebviewCursorIcon::Wait => CursorIcon::Wait,
+        WebviewCursorIcon::Progress => CursorIcon::Progress,
+        WebviewCursorIcon::Help => CursorIcon::Help,
+        WebviewCursorIcon::NotAllowed => CursorIcon::NotAllowed,
+        WebviewCursorIcon::Grab => CursorIcon::Grab,
+        WebviewCursorIcon::Grabbing => CursorIcon::Grabbing,
+    }
+}
+
+/// Convert a Bevy mouse button into a webview mouse button
+pub(crate) fn to_webview_mouse_button(button: MouseButton) -> WebviewMouseButton {
+    match button {
+        MouseButton::Left => WebviewMouseButton::Left,
+        MouseButton::Right => WebviewMouseButton::Right,
+        MouseButton::Middle => WebviewMouseButton::Middle,
+        MouseButton::Other(value) => WebviewMouseButton::Other(value),
+    }
+}
+
+/// Whether the key types text when pressed without Ctrl/Alt/Logo modifiers
+///
+/// The presses of such keys are paired with the characters they typed, see [`key_characters`]
+pub(crate) fn produces_text(key_code: KeyCode, modifiers: Modifiers) -> bool {
+    if modifiers.contains(Modifiers::CTRL)
+        || modifiers.contains(Modifiers::ALT)
+        || modifiers.contains(Modifiers::LOGO)
+    {
+        return false;
+    }
+
+    matches!(
+        key_code,
+        KeyCode::Key1
+            | KeyCode::Key2
+            | KeyCode::Key3
+            | KeyCode::Key4
+            | KeyCode::Key5
+            | KeyCode::Key6
+            | KeyCode::Key7
+            | KeyCode::Key8
+            | KeyCode::Key9
+            | KeyCode::Key0
+            | KeyCode::A
+            | KeyCode::B
+            | KeyCode::C
+            | KeyCode::D
+            | KeyCode::E
+            | KeyCode::F
+            | KeyCode::G
+            | KeyCode::H
+            | KeyCode::I
+            | KeyCode::J
+            | KeyCode::K
+            | KeyCode::L
+            | KeyCode::M
+            | KeyCode::N
+            | KeyCode::O
+            | KeyCode::P
+            | KeyCode::Q
+            | KeyCode::R
+            | KeyCode::S
+            | KeyCode::T
+            | KeyCode::U
+            | KeyCode::V
+            | KeyCode::W
+            | KeyCode::X
+            | KeyCode::Y
+            | KeyCode::Z
+            | KeyCode::Space
+            | KeyCode::Caret
+            | KeyCode::Numpad0
+            | KeyCode::Numpad1
+            | KeyCode::Numpad2
+            | KeyCode::Numpad3
+            | KeyCode::Numpad4
+            | KeyCode::Numpad5
+            | KeyCode::Numpad6
+            | KeyCode::Numpad7
+            | KeyCode::Numpad8
+            | KeyCode::Numpad9
+            | KeyCode::NumpadAdd
+            | KeyCode::NumpadComma
+            | KeyCode::NumpadDecimal
+            | KeyCode::NumpadDivide
+            | KeyCode::NumpadEquals
+            | KeyCode::NumpadMultiply
+            | KeyCode::NumpadSubtract
+            | KeyCode::Apostrophe
+            | KeyCode::Asterisk
+            | KeyCode::Plus
+            | KeyCode::At
+            | KeyCode::Backslash
+            | KeyCode::Colon
+            | KeyCode::Comma
+            | KeyCode::Equals
+            | KeyCode::Grave
+            | KeyCode::LBracket
+            | KeyCode::Minus
+            | KeyCode::Oem102
+            | KeyCode::Period
+            | KeyCode::RBracket
+            | KeyCode::Semicolon
+            | KeyCode::Slash
+            | KeyCode::Underline
+            | KeyCode::Yen
+    )
+}
+
+/// Convert a Bevy key code into a webview key code
+pub(crate) fn to_webview_key_code(key_code: KeyCode) -> WebviewKeyCode {
+    match key_code {
+        KeyCode::Key1 => WebviewKeyCode::Key1,
+        KeyCode::Key2 => WebviewKeyCode::Key2,
+        KeyCode::Key3 => WebviewKeyCode::Key3,
+        KeyCode::Key4 => WebviewKeyCode::Key4,
+        KeyCode::Key5 => WebviewKeyCode::Key5,
+        KeyCode::Key6 => WebviewKeyCode::Key6,
+        KeyCode::Key7 => WebviewKeyCode::Key7,
+        KeyCode::Key8 => WebviewKeyCode::Key8,
+        KeyCode::Key9 => WebviewKeyCode::Key9,
+        KeyCode::Key0 => WebviewKeyCode::Key0,
+        KeyCode::A => WebviewKeyCode::A,
+        KeyCode::B => WebviewKeyCode::B,
+        KeyCode::C => WebviewKeyCode::C,
+        KeyCode::D => WebviewKeyCode::D,
+        KeyCode::E => WebviewKeyCode::E,
+        KeyCode::F => WebviewKeyCode::F,
+        KeyCode::G => WebviewKeyCode::G,
+        KeyCode::H => WebviewKeyCode::H,
+        KeyCode::I => WebviewKeyCode::I,
+        KeyCode::J => WebviewKeyCode::J,
+        KeyCode::K => WebviewKeyCode::K,
+        KeyCode::L => WebviewKeyCode::L,
+        KeyCode::M => WebviewKeyCode::M,
+        KeyCode::N => WebviewKeyCode::N,
+        KeyCode::O => WebviewKeyCode::O,
+        KeyCode::P => WebviewKeyCode::P,
+        KeyCode::Q => WebviewKeyCode::Q,
+        KeyCode::R => WebviewKeyCode::R,
+        KeyCode::S => WebviewKeyCode::S,
+        KeyCode::T => WebviewKeyCode::T,
+        KeyCode::U => WebviewKeyCode::U,
+        KeyCode::V => WebviewKeyCode::V,
+        KeyCode::W => WebviewKeyCode::W,
+        KeyCode::X => WebviewKeyCode::X,
+        KeyCode::Y => WebviewKeyCode::Y,
+        KeyCode::Z => WebviewKeyCode::Z,
+        KeyCode::Escape => WebviewKeyCode::Escape,
+        KeyCode::F1 => WebviewKeyCode::F1,
+        KeyCode::F2 => WebviewKeyCode::F2,
+        KeyCode::F3 => WebviewKeyCode::F3,
+        KeyCode::F4 => WebviewKeyCode::F4,
+        KeyCode::F5 => WebviewKeyCode::F5,
+        KeyCode::F6 => WebviewKeyCode::F6,
+        KeyCode::F7 => WebviewKeyCode::F7,
+        KeyCode::F8 => WebviewKeyCode::F8,
+        KeyCode::F9 => WebviewKeyCode::F9,
+        KeyCode::F10 => WebviewKeyCode::F10,
+        KeyCode::F11 => WebviewKeyCode::F11,
+        KeyCode::F12 => WebviewKeyCode::F12,
+        KeyCode::F13 => WebviewKeyCode::F13,
+        KeyCode::F14 => WebviewKeyCode::F14,
+        KeyCode::F15 => WebviewKeyCode::F15,
+        KeyCode::F16 => WebviewKeyCode::F16,
+        KeyCode::F17 => WebviewKeyCode::F17,
+        KeyCode::F18 => WebviewKeyCode::F18,
+        KeyCode::F19 => WebviewKeyCode::F19,
+        KeyCode::F20 => WebviewKeyCode::F20,
+        KeyCode::F21 => WebviewKeyCode::F21,
+        KeyCode::F22 => WebviewKeyCode::F22,
+        KeyCode::F23 => WebviewKeyCode::F23,
+        KeyCode::F24 => WebviewKeyCode::F24,
+        KeyCode::Snapshot => WebviewKeyCode::Snapshot,
+        KeyCode::Scroll => WebviewKeyCode::Scroll,
+        KeyCode::Pause => WebviewKeyCode::Pause,
+        KeyCode::Insert => WebviewKeyCode::Insert,
+        KeyCode::Home => WebviewKeyCode::Home,
+        KeyCode::Delete => WebviewKeyCode::Delete,
+        KeyCode::End => WebviewKeyCode::End,
+        KeyCode::PageDown => WebviewKeyCode::PageDown,
+        KeyCode::PageUp => WebviewKeyCode::PageUp,
+        KeyCode::Left => WebviewKeyCode::Left,
+        KeyCode::Up => WebviewKeyCode::Up,
+        KeyCode::Right => WebviewKeyCode::Right,
+        KeyCode::Down => WebviewKeyCode::Down,
+        KeyCode::Back => WebviewKeyCode::Back,
+        KeyCode::Return => WebviewKeyCode::Return,
+        KeyCode::Space => WebviewKeyCode::Space,
+        KeyCode::Compose => WebviewKeyCode::Compose,
+        KeyCode::Caret => WebviewKeyCode::Caret,
+        KeyCode::Numlock => WebviewKeyCode::Numlock,
+        KeyCode::Numpad0 => WebviewKeyCode::Numpad0,
+        KeyCode::Numpad1 => WebviewKeyCode::Numpad1,
+        KeyCode::Numpad2 => WebviewKeyCode::Numpad2,
+        KeyCode::Numpad3 => WebviewKeyCode::Numpad3,
+        KeyCode::Numpad4 => WebviewKeyCode::Numpad4,
+        KeyCode::Numpad5 => WebviewKeyCode::Numpad5,
+        KeyCode::Numpad6 => WebviewKeyCode::Numpad6,
+        KeyCode::Numpad7 => WebviewKeyCode::Numpad7,
+        KeyCode::Numpad8 => WebviewKeyCode::Numpad8,
+        KeyCode::Numpad9 => WebviewKeyCode::Numpad9,
+        KeyCode::AbntC1 => WebviewKeyCode::AbntC1,
+        KeyCode::AbntC2 => WebviewKeyCode::AbntC2,
+        KeyCode::NumpadAdd => WebviewKeyCode::NumpadAdd,
+        KeyCode::Apostrophe => WebviewKeyCode::Apostrophe,
+        KeyCode::Apps => WebviewKeyCode::Apps,
+        KeyCode::Asterisk => WebviewKeyCode::Asterisk,
+        KeyCode::Plus => WebviewKeyCode::Plus,
+        KeyCode::At => WebviewKeyCode::At,
+        KeyCode::Ax => WebviewKeyCode::Ax,
+        KeyCode::Backslash => WebviewKeyCode::Backslash,
+        KeyCode::Calculator => WebviewKeyCode::Calculator,
+        KeyCode::Capital => WebviewKeyCode::Capital,
+        KeyCode::Colon => WebviewKeyCode::Colon,
+        KeyCode::Comma => WebviewKeyCode::Comma,
+        KeyCode::Convert => WebviewKeyCode::Convert,
+        KeyCode::NumpadDecimal => WebviewKeyCode::NumpadDecimal,
+        KeyCode::NumpadDivide => WebviewKeyCode::NumpadDivide,
+        KeyCode::Equals => WebviewKeyCode::Equals,
+        KeyCode::Grave => WebviewKeyCode::Grave,
+        KeyCode::Kana => WebviewKeyCode::Kana,
+        KeyCode::Kanji => WebviewKeyCode::Kanji,
+        KeyCode::LAlt => WebviewKeyCode::LAlt,
+        KeyCode::LBracket => WebviewKeyCode::LBracket,
+        KeyCode::LControl => WebviewKeyCode::LControl,
+        KeyCode::LShift => WebviewKeyCode::LShift,
+        KeyCode::LWin => WebviewKeyCode::LWin,
+        KeyCode::Mail => WebviewKeyCode::Mail,
+        KeyCode::MediaSelect => WebviewKeyCode::MediaSelect,
+        KeyCode::MediaStop => WebviewKeyCode::MediaStop,
+        KeyCode::Minus => WebviewKeyCode::Minus,
+        KeyCode::NumpadMultiply => WebviewKeyCode::NumpadMultiply,
+        KeyCode::Mute => WebviewKeyCode::Mute,
+        KeyCode::MyComputer => WebviewKeyCode::MyComputer,
+        KeyCode::NavigateForward => WebviewKeyCode::NavigateForward,
+        KeyCode::NavigateBackward => WebviewKeyCode::NavigateBackward,
+        KeyCode::NextTrack => WebviewKeyCode::NextTrack,
+        KeyCode::NoConvert => WebviewKeyCode::NoConvert,
+        KeyCode::NumpadComma => WebviewKeyCode::NumpadComma,
+        KeyCode::NumpadEnter => WebviewKeyCode::NumpadEnter,
+        KeyCode::NumpadEquals => WebviewKeyCode::NumpadEquals,
+        KeyCode::Oem102 => WebviewKeyCode::Oem102,
+        KeyCode::Period => WebviewKeyCode::Period,
+        KeyCode::PlayPause => WebviewKeyCode::PlayPause,
+        KeyCode::Power => WebviewKeyCode::Power,
+        KeyCode::PrevTrack => WebviewKeyCode::PrevTrack,
+        KeyCode::RAlt => WebviewKeyCode::RAlt,
+        KeyCode::RBracket => WebviewKeyCode::RBracket,
+        KeyCode::RControl => WebviewKeyCode::RControl,
+        KeyCode::RShift => WebviewKeyCode::RShift,
+        KeyCode::RWin => WebviewKeyCode::RWin,
+        KeyCode::Semicolon => WebviewKeyCode::Semicolon,
+        KeyCode::Slash => WebviewKeyCode::Slash,
+        KeyCode::Sleep => WebviewKeyCode::Sleep,
+        KeyCode::Stop => WebviewKeyCode::Stop,
+        KeyCode::NumpadSubtract => WebviewKeyCode::NumpadSubtract,
+        KeyCode::Sysrq => WebviewKeyCode::Sysrq,
+        KeyCode::Tab => WebviewKeyCode::Tab,
+        KeyCode::Underline => WebviewKeyCode::Underline,
+        KeyCode::Unlabeled => WebviewKeyCode::Unlabeled,
+        KeyCode::VolumeDown => WebviewKeyCode::VolumeDown,
+        KeyCode::VolumeUp => WebviewKeyCode::VolumeUp,
+        KeyCode::Wake => WebviewKeyCode::Wake,
+        KeyCode::WebBack => WebviewKeyCode::WebBack,
+        KeyCode::WebFavorites => WebviewKeyCode::WebFavorites,
+        KeyCode::WebForward => WebviewKeyCode::WebForward,
+        KeyCode::WebHome => WebviewKeyCode::WebHome,
+        KeyCode::WebRefresh => WebviewKeyCode::WebRefresh,
+        KeyCode::WebSearch => WebviewKeyCode::WebSearch,
+        KeyCode::WebStop => WebviewKeyCode::WebStop,
+        KeyCode::Yen => WebviewKeyCode::Yen,
+        KeyCode::Copy => WebviewKeyCode::Copy,
+        KeyCode::Paste => WebviewKeyCode::Paste,
+        KeyCode::Cut => WebviewKeyCode::Cut,
+    }
+}
+
+/// Pair the characters received during a frame with the key presses that typed them
+///
+/// The characters follow the keyboard layout, and are taken in order by the presses of the keys
+/// that [`produces_text`]. Returns the character of each keyboard event, and the text that no key
+/// typed (dead key compositions, committed IME text, ...). Control characters are left to their
+/// keys
+pub(crate) fn key_characters<'a>(
+    keyboard_events: impl IntoIterator<Item = &'a KeyboardInput>,
+    characters: impl IntoIterator<Item = char>,
+    modifiers: Modifiers,
+) -> (Vec<Option<char>>, String) {
+    let mut characters = characters.into_iter().filter(|char| !char.is_control());
+
+    let key_characters = keyboard_events
+        .into_iter()
+        .map(|keyboard_event| {
+            let types_text = keyboard_event.state == ElementState::Pressed
+                && keyboard_event
+                    .key_code
+                    .map(|key_code| produces_text(key_code, modifiers))
+                    .unwrap_or(false);
+
+            if types_text {
+                characters.next()
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    (key_characters, characters.collect())
+}
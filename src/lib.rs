@@ -26,6 +26,9 @@
 //!     });
 //! }
 //! ```
+use std::collections::HashSet;
+use std::path::PathBuf;
+
 use bevy::{
     prelude::*,
     ui::{widget::ImageMode, UiSystem},
@@ -33,26 +36,48 @@ use bevy::{
 
 pub mod prelude {
     pub use crate::{
-        Webview, WebviewApp, WebviewBundle, WebviewCommand, WebviewEventReader, WebviewEventWriter,
-        WebviewPlugin, WebviewSize, WebviewUIBundle,
+        Webview, WebviewApp, WebviewBundle, WebviewCaptured, WebviewCommand, WebviewCursorChanged,
+        WebviewEventReader, WebviewEventWriter, WebviewFocus, WebviewFrameBuffers,
+        WebviewOutboxDropPolicy, WebviewOutboxSettings, WebviewPlugin, WebviewRecording,
+        WebviewRecordingFormat, WebviewRenderMode, WebviewRenderSettings, WebviewRpcError,
+        WebviewRpcParams, WebviewRpcRequests, WebviewRpcResponder, WebviewSize,
+        WebviewSpatialNavigation, WebviewSpriteBundle, WebviewTarget, WebviewUIBundle,
     };
 
     pub use headless_webview::engines;
 
+    #[cfg(feature = "typescript")]
+    pub use crate::WebviewSchemaApp;
+
     #[cfg(feature = "engine")]
     pub use headless_webview_engine as webview_engine;
 }
 
 pub use serde;
 
+#[cfg(feature = "typescript")]
+pub use schemars;
+#[cfg(feature = "typescript")]
+pub use typescript::{typescript_definitions, WebviewSchemaApp};
+
 mod events;
+mod frame_buffers;
+mod input;
 mod systems;
 mod types;
+#[cfg(feature = "typescript")]
+mod typescript;
 mod webview;
 use events::{
-    BuiltinWebviewEvent, InputEvent, InputEventMapping, OutputEventMapping, WebviewEvent,
+    BinaryEventMapping, BuiltinWebviewEvent, InputEvent, InputEventMapping, OutputEventMapping,
+    RpcMethodMapping, WebviewEvent,
 };
-pub use events::{WebviewApp, WebviewEventReader, WebviewEventWriter};
+pub use events::{
+    WebviewApp, WebviewEventReader, WebviewEventWriter, WebviewRpcError, WebviewRpcParams,
+    WebviewRpcRequest, WebviewRpcRequests, WebviewRpcResponder,
+};
+pub use frame_buffers::{FrameBufferStats, WebviewFrameBuffers};
+pub use headless_webview::types::{Texture, TextureFormat};
 use headless_webview::HeadlessWindow;
 use headless_webview::WindowBuilder;
 use serde::Serialize;
@@ -61,6 +86,9 @@ use webview::webview_thread;
 
 pub(crate) const BUILTIN_RPC_INPUT_METHOD: &str = "_webview";
 
+/// Output event of the deserialization errors of notifications, see [`WebviewRpcError`]
+pub(crate) const RPC_ERROR_OUTPUT_METHOD: &str = "_error";
+
 /// The webview plugin
 ///
 /// Plugin configuration requires an engine registration
@@ -73,11 +101,21 @@ pub(crate) const BUILTIN_RPC_INPUT_METHOD: &str = "_webview";
 /// ```
 pub struct WebviewPlugin<ENGINE: HeadlessWindow> {
     pub(crate) engine: Option<fn() -> WindowBuilder<ENGINE>>,
+    pub(crate) cursor_icons: bool,
+    pub(crate) outbox: WebviewOutboxSettings,
+    #[cfg(feature = "typescript")]
+    pub(crate) typescript_definitions: Option<PathBuf>,
 }
 
 impl<ENGINE: HeadlessWindow> WebviewPlugin<ENGINE> {
     pub fn new() -> Self {
-        Self { engine: None }
+        Self {
+            engine: None,
+            cursor_icons: true,
+            outbox: WebviewOutboxSettings::default(),
+            #[cfg(feature = "typescript")]
+            typescript_definitions: None,
+        }
     }
 }
 
@@ -85,6 +123,10 @@ impl<ENGINE: HeadlessWindow> WebviewPlugin<ENGINE> {
     pub fn with_engine(engine: fn() -> WindowBuilder<ENGINE>) -> Self {
         Self {
             engine: Some(engine),
+            cursor_icons: true,
+            outbox: WebviewOutboxSettings::default(),
+            #[cfg(feature = "typescript")]
+            typescript_definitions: None,
         }
     }
 
@@ -92,6 +134,54 @@ impl<ENGINE: HeadlessWindow> WebviewPlugin<ENGINE> {
         self.engine = Some(engine);
         self
     }
+
+    /// Whether the primary window cursor icon follows the page while a webview is hovered
+    /// (enabled by default). [`WebviewCursorChanged`] events are sent in both cases
+    pub fn with_cursor_icons(mut self, enabled: bool) -> Self {
+        self.cursor_icons = enabled;
+        self
+    }
+
+    /// How output events are buffered until the page RPC is initialized
+    pub fn with_outbox(mut self, outbox: WebviewOutboxSettings) -> Self {
+        self.outbox = outbox;
+        self
+    }
+
+    /// Write the TypeScript definitions of the registered events and RPC methods to `path` on
+    /// startup, see [`typescript_definitions`]
+    ///
+    /// The payload types are registered with [`WebviewSchemaApp`], and implement
+    /// `schemars::JsonSchema`, re-exported as [`schemars`]
+    ///
+    /// ```rust
+    /// # use bevy_webview::serde::Deserialize;
+    /// use bevy::prelude::*;
+    /// use bevy_webview::{prelude::*, schemars::JsonSchema};
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// pub struct LoginRequest {
+    ///     username: String,
+    /// }
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins(DefaultPlugins)
+    ///         .add_plugin(
+    ///             WebviewPlugin::new()
+    ///                 .register_engine(webview_engine::headless)
+    ///                 .with_typescript_definitions("frontend/src/webview.d.ts"),
+    ///         )
+    ///         .add_webview_input_event::<LoginRequest>("login")
+    ///         .add_webview_event_schema::<LoginRequest>();
+    ///         // .run();
+    /// }
+    /// ```
+    #[cfg(feature = "typescript")]
+    pub fn with_typescript_definitions(mut self, path: impl Into<PathBuf>) -> Self {
+        self.typescript_definitions = Some(path.into());
+        self
+    }
 }
 
 impl<ENGINE> Plugin for WebviewPlugin<ENGINE>
@@ -99,25 +189,44 @@ where
     ENGINE: HeadlessWindow + 'static,
 {
     fn build(&self, app: &mut App) {
-        let event_transport = webview_thread(WebviewEngine(
-            self.engine
-                .expect("Webview is missing an engine. Please use `.register_engine(engine)`"),
-        ));
+        let event_transport = webview_thread(
+            WebviewEngine(
+                self.engine
+                    .expect("Webview is missing an engine. Please use `.register_engine(engine)`"),
+            ),
+            self.outbox,
+        );
 
         app.insert_resource(InputEventMapping::default())
             .insert_resource(OutputEventMapping::default())
+            .insert_resource(RpcMethodMapping::default())
+            .insert_resource(BinaryEventMapping::default())
+            .insert_resource(event_transport.frame_buffers.clone())
             .insert_resource(event_transport)
+            .init_resource::<WebviewFocus>()
             .add_event::<InputEvent>()
             .add_event::<WebviewEvent<WebviewCommand>>()
+            .add_event::<WebviewCursorChanged>()
+            .add_event::<WebviewCaptured>()
             .add_webview_input_event::<BuiltinWebviewEvent>(BUILTIN_RPC_INPUT_METHOD)
             // PRE-SYSTEMS
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 systems::inject_rpc_requests_system.label(PreUpdateLabel::Pre),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                systems::webview_cursor_event_system.label(PreUpdateLabel::Pre),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                systems::webview_capture_event_system.label(PreUpdateLabel::Pre),
+            )
             // Systems
             .add_system(systems::rpc_builtin_event_handler)
             .add_system(systems::webview_ui_focus_system)
+            .add_system(systems::webview_ui_touch_system)
+            .add_system(systems::spatial_navigation_system)
             .add_system(systems::ui_event)
             // PRE-PRE-POST updates - send events to webview
             .add_system_to_stage(
@@ -126,6 +235,10 @@ where
                     .label(PostUpdateLabel::PrePre)
                     .after(UiSystem::Flex),
             )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                systems::webview_size_system.label(PostUpdateLabel::PrePre),
+            )
             // PRE-POST updates - send events to webview
             .add_system_to_stage(
                 CoreStage::PostUpdate,
@@ -137,6 +250,16 @@ where
                 CoreStage::PostUpdate,
                 systems::keyboard_event_system.label(PostUpdateLabel::Pre),
             )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                systems::text_input_event_system.label(PostUpdateLabel::Pre),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                systems::webview_focus_system
+                    .label(PostUpdateLabel::Pre)
+                    .after(systems::keyboard_event_system),
+            )
             .add_system_to_stage(
                 CoreStage::PostUpdate,
                 systems::webview_changed_system.label(PostUpdateLabel::Pre),
@@ -175,6 +298,16 @@ where
                     .label(PostUpdateLabel::Post)
                     .after(PostUpdateLabel::Update),
             );
+
+        if self.cursor_icons {
+            app.add_system(systems::webview_cursor_icon_system);
+        }
+
+        #[cfg(feature = "typescript")]
+        if let Some(path) = &self.typescript_definitions {
+            app.insert_resource(typescript::TypescriptDefinitionsPath(path.clone()))
+                .add_startup_system(typescript::write_typescript_definitions);
+        }
     }
 }
 
@@ -210,11 +343,16 @@ pub struct WebviewBundle {
     /// The global transform of the node
     pub global_transform: GlobalTransform,
 
+    /// Interaction state
+    pub interaction: WebviewInteraction,
+
     /// Internal webview state, should not be edited directly
     pub webview_state: WebviewState,
 }
 
 /// Webview canvas size, used as a part of `WebviewBundle`. 2D/UI size is calculated automatically
+///
+/// May be changed at runtime, the webview page, its quad (or sprite) and texture are resized
 #[derive(Component, Debug, Clone)]
 pub struct WebviewSize {
     /// Width (in bevy world scale)
@@ -251,6 +389,59 @@ impl WebviewSize {
     }
 }
 
+/// Webview bundle for 2D games, rendered as a [`Sprite`] and layered by `Transform.z` like other
+/// sprites. The sprite size is set by [`WebviewSize`], in world units
+#[derive(Bundle, Clone, Debug)]
+pub struct WebviewSpriteBundle {
+    /// WebView configuration
+    pub webview: Webview,
+
+    /// Size configuration
+    pub size: WebviewSize,
+
+    /// Sprite configuration, `custom_size` is overridden by `size`
+    pub sprite: Sprite,
+
+    /// Sprite texture, managed by the webview
+    pub texture: Handle<Image>,
+
+    /// The transform of the sprite
+    pub transform: Transform,
+
+    /// The global transform of the sprite
+    pub global_transform: GlobalTransform,
+
+    /// Describes the visibility properties of the sprite
+    pub visibility: Visibility,
+
+    /// Interaction state
+    pub interaction: WebviewInteraction,
+
+    /// Internal webview state, should not be edited directly
+    pub webview_state: WebviewState,
+}
+
+impl Default for WebviewSpriteBundle {
+    fn default() -> Self {
+        Self {
+            webview: Default::default(),
+            // with the default 2D camera, one world unit is one logical pixel
+            size: WebviewSize {
+                x: 400.,
+                y: 300.,
+                ppu: 1.,
+            },
+            sprite: Default::default(),
+            texture: Default::default(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            interaction: Default::default(),
+            webview_state: Default::default(),
+        }
+    }
+}
+
 /// Webview bundle needed for creating an UI webview. For 3D mode, see [`WebviewBundle`]
 #[derive(Bundle, Clone, Default, Debug)]
 pub struct WebviewUIBundle {
@@ -285,7 +476,41 @@ pub struct WebviewUIBundle {
     pub webview_state: WebviewState,
 }
 
+/// Renders the webview into an image of your own, e.g. for custom materials or several meshes
+///
+/// The image is updated in place, and reallocated (keeping the handle) when the webview is resized.
+/// With [`WebviewSize`], no quad is spawned for the webview; the size only sets the resolution. UI
+/// webviews display the image.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_webview::prelude::*;
+///
+/// fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+///     let image = images.add(Image::default());
+///
+///     commands
+///         .spawn_bundle(WebviewBundle {
+///             webview: Webview {
+///                 html: Some(String::from("<h1>Hello</h1>")),
+///                 ..Default::default()
+///             },
+///             ..Default::default()
+///         })
+///         .insert(WebviewTarget(image.clone()));
+///
+///     // use `image` in any number of materials / sprites
+/// }
+/// ```
+#[derive(Component, Clone, Debug, Default)]
+pub struct WebviewTarget(pub Handle<Image>);
+
 /// Webview [`Component`], should be inserted as a part of [`WebviewBundle`] or [`WebviewUIBundle`]
+///
+/// Insert an [`AlphaMode`] next to it to choose how the page is blended (`AlphaMode::Blend` by
+/// default). 3D webviews set it on their material, UI and sprite webviews apply it to the texture.
 #[derive(Component, Clone, Debug)]
 pub struct Webview {
     /// Load the provided URL
@@ -296,7 +521,7 @@ pub struct Webview {
     /// Load the provided HTML string
     pub html: Option<String>,
 
-    /// Color
+    /// Color, shown until the first frame. An alpha below 1 makes the page background transparent
     pub color: Color,
 
     /// Extra javascript that may be used for initialization (e.g. variable / state setup)
@@ -314,6 +539,204 @@ impl Default for Webview {
     }
 }
 
+/// Keyboard focus of the webviews, available as a resource
+///
+/// Clicking or touching a webview focuses it, while pressing `Escape` or clicking outside of the
+/// webviews blurs it. Keyboard input is sent only to the focused webview.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_webview::prelude::*;
+///
+/// fn hotkeys(keyboard_input: Res<Input<KeyCode>>, focus: Res<WebviewFocus>) {
+///     // the user is typing into a webview text field
+///     if focus.is_editing() {
+///         return;
+///     }
+///
+///     if keyboard_input.just_pressed(KeyCode::M) {
+///         println!("Open map");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WebviewFocus {
+    focused: Option<Entity>,
+    editing: HashSet<Entity>,
+    hovered: Option<Entity>,
+}
+
+impl WebviewFocus {
+    /// The focused webview, if any
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    /// Move the keyboard focus to the webview
+    pub fn focus(&mut self, entity: Entity) {
+        self.focused = Some(entity);
+    }
+
+    /// Remove the keyboard focus from webviews
+    pub fn blur(&mut self) {
+        self.focused = None;
+    }
+
+    /// Whether the focused webview has a focused editable element (e.g. an `<input>`)
+    pub fn is_editing(&self) -> bool {
+        self.focused
+            .map_or(false, |entity| self.editing.contains(&entity))
+    }
+
+    /// The topmost webview under the mouse cursor, if any
+    pub fn hovered(&self) -> Option<Entity> {
+        self.hovered
+    }
+
+    pub(crate) fn set_hovered(&mut self, hovered: Option<Entity>) {
+        self.hovered = hovered;
+    }
+
+    pub(crate) fn set_editing(&mut self, entity: Entity, editing: bool) {
+        if editing {
+            self.editing.insert(entity);
+        } else {
+            self.editing.remove(&entity);
+        }
+    }
+}
+
+/// Gamepad navigation for webviews used without a pointer, e.g. menus on consoles
+///
+/// When inserted to a webview entity, D-pad and left stick move the focus between the focusable
+/// elements of the page, and the `activate` / `back` buttons click the focused element / dispatch a
+/// cancelable `navigateback` DOM event from it. Navigation is sent to the focused webview, or to all
+/// navigable webviews when none is focused. The navigation script is only injected into webviews
+/// spawned with this component.
+#[derive(Component, Clone, Debug)]
+pub struct WebviewSpatialNavigation {
+    /// Button that activates (clicks) the focused element
+    pub activate: GamepadButtonType,
+
+    /// Button that dispatches `navigateback`
+    pub back: GamepadButtonType,
+}
+
+impl Default for WebviewSpatialNavigation {
+    fn default() -> Self {
+        Self {
+            activate: GamepadButtonType::South,
+            back: GamepadButtonType::East,
+        }
+    }
+}
+
+/// Limits how often the texture of a webview is captured, e.g. for background HUD panels
+///
+/// Webviews without this component are captured on every frame.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy_webview::prelude::*;
+///
+/// // at most 10 captures per second, and only after input or navigation
+/// let settings = WebviewRenderSettings {
+///     max_fps: Some(10.),
+///     mode: WebviewRenderMode::OnDemand,
+/// };
+/// ```
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct WebviewRenderSettings {
+    /// Maximum texture captures per second, `None` for no limit
+    pub max_fps: Option<f32>,
+
+    /// When the webview is captured
+    pub mode: WebviewRenderMode,
+}
+
+/// Capture mode of a webview, see [`WebviewRenderSettings`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WebviewRenderMode {
+    /// Capture on every frame
+    #[default]
+    Continuous,
+
+    /// Capture only for a moment after input, navigation or [`WebviewCommand::RequestFrame`]
+    OnDemand,
+
+    /// Capture only once on [`WebviewCommand::RequestFrame`], the page still runs and handles
+    /// input
+    Paused,
+}
+
+/// Buffering of output events ([`WebviewEventWriter`]) sent before the page is ready to receive
+/// them: after the spawn, and after every navigation. Buffered events are delivered in order once
+/// the page RPC is initialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WebviewOutboxSettings {
+    /// Maximum buffered events per webview
+    pub capacity: usize,
+
+    /// Which events are dropped when the buffer is full
+    pub drop_policy: WebviewOutboxDropPolicy,
+}
+
+impl Default for WebviewOutboxSettings {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            drop_policy: WebviewOutboxDropPolicy::DropOldest,
+        }
+    }
+}
+
+/// Events dropped from a full outbox, see [`WebviewOutboxSettings`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebviewOutboxDropPolicy {
+    /// Drop the oldest buffered event, e.g. for state updates where the latest one wins
+    DropOldest,
+
+    /// Drop the events that do not fit
+    DropNewest,
+}
+
+/// Sent when the page of a webview requests a different mouse cursor icon, e.g. when hovering a link
+#[derive(Debug, Clone)]
+pub struct WebviewCursorChanged {
+    /// The webview
+    pub entity: Entity,
+
+    /// Requested cursor icon
+    pub icon: CursorIcon,
+}
+
+/// Screenshot of a webview, requested by [`WebviewCommand::Capture`]
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_webview::prelude::*;
+///
+/// fn save_screenshots(mut captures: EventReader<WebviewCaptured>) {
+///     for capture in captures.iter() {
+///         let png = capture.image.encode_png().unwrap();
+///         std::fs::write(format!("webview-{}.png", capture.entity.id()), png).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebviewCaptured {
+    /// The webview
+    pub entity: Entity,
+
+    /// Captured page, in full
+    pub image: Texture,
+}
+
 /// Webview Commands for controlling a webview instance
 ///
 /// Any future command added here should be available in the core API's:
@@ -332,6 +755,72 @@ pub enum WebviewCommand {
 
     /// Executes the given Javascript string
     RunJavascript(String),
+
+    /// Captures a new frame on the next tick, also when on-demand or paused (see
+    /// [`WebviewRenderSettings`])
+    RequestFrame,
+
+    /// Takes a screenshot of the page, sent as a [`WebviewCaptured`] event
+    Capture {
+        /// Capture the whole document instead of the visible viewport
+        full_page: bool,
+    },
+
+    /// Starts writing every frame of the webview to disk, see [`WebviewRecording`]
+    StartRecording(WebviewRecording),
+
+    /// Stops the recording, and writes the frame timing metadata
+    StopRecording,
+}
+
+/// Frame recording settings of [`WebviewCommand::StartRecording`]
+///
+/// Frames are written into `directory` along with `frames.json`, containing the frame timestamps
+/// (milliseconds since the start of the recording). Frames are encoded in the background, and
+/// dropped when the disk cannot keep up - their count is `dropped_frames` in `frames.json`.
+/// When the command is sent to all webviews, each webview records into its own
+/// `webview_<entity id>` subdirectory.
+///
+/// # Example
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_webview::prelude::*;
+///
+/// fn record(keyboard_input: Res<Input<KeyCode>>, mut commands: WebviewEventWriter<WebviewCommand>) {
+///     if keyboard_input.just_pressed(KeyCode::R) {
+///         commands.send(WebviewCommand::StartRecording(WebviewRecording {
+///             directory: "recordings".into(),
+///             format: WebviewRecordingFormat::Png,
+///         }));
+///     }
+///
+///     if keyboard_input.just_released(KeyCode::R) {
+///         commands.send(WebviewCommand::StopRecording);
+///     }
+/// }
+/// ```
+#[derive(Serialize, Debug, Clone)]
+pub struct WebviewRecording {
+    /// Output directory, created if missing
+    pub directory: PathBuf,
+
+    /// Output format
+    pub format: WebviewRecordingFormat,
+}
+
+/// Output format of a [`WebviewRecording`]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebviewRecordingFormat {
+    /// Numbered PNG files, `frame_000000.png` onwards
+    Png,
+
+    /// Uncompressed 4:4:4 YUV stream (`frames.y4m`), at a nominal frame rate. The actual frame
+    /// times are in `frames.json`. Frames of other sizes than the first one are skipped
+    Y4m {
+        /// Frame rate of the stream header
+        fps: u32,
+    },
 }
 
 /// Internal webview state, should not be edited directly
@@ -1,28 +1,58 @@
 use bevy::{input::keyboard::KeyboardInput, prelude::*};
 
-use crate::types::{EventTransport, WebviewAction};
-
-use super::WebviewInteraction;
+use crate::{
+    input::{key_characters, modifiers_from_input},
+    types::{EventTransport, WebviewAction},
+    WebviewFocus,
+};
 
 /// Maps bevy keyboard inputs to webview
 ///
-/// Keyboard inputs are sent only when the webview component is being hovered or was clicked
+/// Keyboard inputs are sent only to the focused webview, and `Escape` blurs it.
+/// The keys that type text carry the character they typed, the text that no key typed is left to
+/// [`text_input_event_system`](super::text_input_event_system)
 pub(crate) fn keyboard_event_system(
     event_transport: ResMut<EventTransport>,
-    webview_query: Query<(Entity, &WebviewInteraction)>,
+    mut focus: ResMut<WebviewFocus>,
     mut keyboard_input_events: EventReader<KeyboardInput>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
 ) {
-    for (entity, interaction) in webview_query.iter() {
-        if let WebviewInteraction::Clicked(_) | WebviewInteraction::Hovered(_) = interaction {
-            for keyboard_event in keyboard_input_events.iter() {
-                event_transport
-                    .webview_action_tx
-                    .send(WebviewAction::TypeKeyboard((
-                        entity,
-                        keyboard_event.clone(),
-                    )))
-                    .unwrap();
-            }
+    let characters: Vec<char> = received_characters
+        .iter()
+        .map(|received_character| received_character.char)
+        .collect();
+
+    // the events are drained also when not sent, so that they are not delivered a frame late to a
+    // webview focused meanwhile
+    let entity = match focus.focused() {
+        Some(entity) => entity,
+        None => {
+            keyboard_input_events.iter().for_each(drop);
+            return;
         }
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        keyboard_input_events.iter().for_each(drop);
+        focus.blur();
+        return;
+    }
+
+    let modifiers = modifiers_from_input(&keyboard_input);
+
+    let keyboard_events: Vec<&KeyboardInput> = keyboard_input_events.iter().collect();
+    let (texts, _) = key_characters(keyboard_events.iter().copied(), characters, modifiers);
+
+    for (keyboard_event, text) in keyboard_events.into_iter().zip(texts) {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::TypeKeyboard((
+                entity,
+                keyboard_event.clone(),
+                modifiers,
+                text,
+            )))
+            .unwrap();
     }
 }
@@ -1,33 +1,53 @@
 mod app_exit_system;
 mod keyboard_event_system;
+mod rpc_binary_event_receiver;
+mod rpc_binary_event_sender;
 mod rpc_builtin_event_handler;
 mod rpc_command_system;
 mod rpc_event_receiver;
 mod rpc_event_sender;
 mod rpc_fallthrough_event_logger;
 mod rpc_inject_requests_system;
+mod rpc_request_receiver;
+mod spatial_navigation_system;
+mod text_input_event_system;
 mod ui_event;
 mod ui_focus_system;
 mod ui_size_system;
+mod ui_touch_system;
+mod webview_capture_system;
 mod webview_changed_system;
 mod webview_create_system;
+mod webview_cursor_system;
+mod webview_focus_system;
 mod webview_remove_system;
+mod webview_size_system;
 mod webview_tick_system;
 mod webview_update_textures;
 
 pub(crate) use app_exit_system::*;
 pub(crate) use keyboard_event_system::*;
+pub(crate) use rpc_binary_event_receiver::*;
+pub(crate) use rpc_binary_event_sender::*;
 pub(crate) use rpc_builtin_event_handler::*;
 pub(crate) use rpc_command_system::*;
 pub(crate) use rpc_event_receiver::*;
 pub(crate) use rpc_event_sender::*;
 pub(crate) use rpc_fallthrough_event_logger::*;
 pub(crate) use rpc_inject_requests_system::*;
+pub(crate) use rpc_request_receiver::*;
+pub(crate) use spatial_navigation_system::*;
+pub(crate) use text_input_event_system::*;
 pub(crate) use ui_event::*;
 pub use ui_focus_system::*;
 pub(crate) use ui_size_system::*;
+pub(crate) use ui_touch_system::*;
+pub(crate) use webview_capture_system::*;
 pub(crate) use webview_changed_system::*;
 pub(crate) use webview_create_system::*;
+pub(crate) use webview_cursor_system::*;
+pub(crate) use webview_focus_system::*;
 pub(crate) use webview_remove_system::*;
+pub(crate) use webview_size_system::*;
 pub(crate) use webview_tick_system::*;
 pub(crate) use webview_update_textures::*;
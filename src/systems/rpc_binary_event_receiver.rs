@@ -0,0 +1,54 @@
+use std::{any::TypeId, sync::atomic::Ordering};
+
+use bevy::{ecs::system::Resource, log, prelude::*};
+
+use crate::{
+    events::{BinaryEventMapping, InputEvent},
+    types::EventTransport,
+    WebviewEvent, WebviewRpcError, WebviewRpcResponder,
+};
+
+pub(crate) fn rpc_binary_event_receiver<T>(
+    mut input_events: EventReader<InputEvent>,
+    mut output_event_writer: EventWriter<WebviewEvent<T>>,
+    binary_event_methods: Res<BinaryEventMapping>,
+    event_transport: Res<EventTransport>,
+) where
+    T: Resource + From<Vec<u8>>,
+{
+    let method = match binary_event_methods.input_events.get(&TypeId::of::<T>()) {
+        Some(v) => *v,
+        None => return,
+    };
+
+    for event in input_events.iter().filter(|v| v.request.method == method) {
+        event.matched.store(true, Ordering::SeqCst);
+
+        let responder = WebviewRpcResponder::<()>::new(
+            event.entity,
+            &event.request,
+            event_transport.webview_action_tx.clone(),
+        );
+
+        match event.request.binary_params() {
+            Ok(bytes) => {
+                log::debug!(
+                    "Received incoming binary event method={:?} ({} bytes)",
+                    method,
+                    bytes.len()
+                );
+                output_event_writer.send(WebviewEvent::new(Some(event.entity), T::from(bytes)));
+                responder.respond(());
+            }
+
+            Err(e) => {
+                log::warn!("method={:?} binary params error: {}", method, e);
+
+                responder.reject(WebviewRpcError::with_code(
+                    WebviewRpcError::INVALID_PARAMS,
+                    format!("{}: {}", method, e),
+                ));
+            }
+        }
+    }
+}
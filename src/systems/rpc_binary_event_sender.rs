@@ -0,0 +1,39 @@
+use std::any::TypeId;
+
+use bevy::{ecs::system::Resource, prelude::*};
+
+use crate::{
+    events::BinaryEventMapping,
+    types::{EventTransport, WebviewAction},
+    Webview, WebviewEvent,
+};
+
+// Will send Bevy events of type `<T>` to webview as bytes
+pub(crate) fn rpc_binary_event_sender<T>(
+    mut event_reader: EventReader<WebviewEvent<T>>,
+    binary_event_methods: Res<BinaryEventMapping>,
+    event_transport: Res<EventTransport>,
+    webviews: Query<Entity, With<Webview>>,
+) where
+    T: Resource + AsRef<[u8]>,
+{
+    if webviews.is_empty() {
+        return;
+    }
+
+    for event in event_reader.iter() {
+        let event_key = binary_event_methods
+            .output_events
+            .get(&TypeId::of::<T>())
+            .unwrap();
+
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::SendBinaryEvent(
+                event.entity,
+                event_key.to_string(),
+                event.val.as_ref().to_vec(),
+            ))
+            .unwrap();
+    }
+}
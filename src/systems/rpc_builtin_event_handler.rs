@@ -1,11 +1,12 @@
 use bevy::prelude::*;
 
-use crate::{events::BuiltinWebviewEvent, types::EventTransport, WebviewEventReader};
+use crate::{events::BuiltinWebviewEvent, types::EventTransport, WebviewEventReader, WebviewFocus};
 
 pub(crate) fn rpc_builtin_event_handler(
     mut events: WebviewEventReader<BuiltinWebviewEvent>,
     mut commands: Commands,
     event_transport: Res<EventTransport>,
+    mut focus: ResMut<WebviewFocus>,
 ) {
     for (event, entity) in events.iter_with_entity() {
         match &event {
@@ -16,9 +17,22 @@ pub(crate) fn rpc_builtin_event_handler(
             BuiltinWebviewEvent::Initialize => {
                 event_transport
                     .webview_action_tx
-                    .send(crate::types::WebviewAction::SetRPCInitialized(entity))
+                    .send(crate::types::WebviewAction::SetRPCInitialized(entity, true))
                     .unwrap();
             }
+
+            BuiltinWebviewEvent::Unload => {
+                event_transport
+                    .webview_action_tx
+                    .send(crate::types::WebviewAction::SetRPCInitialized(
+                        entity, false,
+                    ))
+                    .unwrap();
+            }
+
+            BuiltinWebviewEvent::EditableFocus(editing) => {
+                focus.set_editing(entity, *editing);
+            }
         }
     }
 }
@@ -4,49 +4,58 @@ use bevy::{ecs::system::Resource, log, prelude::*};
 
 use crate::{
     events::{InputEvent, InputEventMapping},
-    WebviewEvent,
+    types::EventTransport,
+    WebviewEvent, WebviewRpcError, WebviewRpcResponder,
 };
 
 pub(crate) fn rpc_event_receiver<T>(
     mut input_events: EventReader<InputEvent>,
     mut output_event_writer: EventWriter<WebviewEvent<T>>,
     input_event_methods: Res<InputEventMapping>,
+    event_transport: Res<EventTransport>,
 ) where
     T: Resource + for<'de> serde::Deserialize<'de>,
 {
-    for event in input_events
-        .iter()
-        .filter(|v| {
-            let expected = Some(v.request.method.as_str());
-            let matched = input_event_methods
-                .events
-                .get(&TypeId::of::<T>())
-                .map(|v| *v);
-            expected == matched
-        })
-        .filter(|v| v.request.params.is_some())
-    {
-        let val: Result<T, serde_json::Error> =
-            serde_json::from_value(event.request.params.as_ref().unwrap()[0].clone());
+    let (method, params) = match input_event_methods.events.get(&TypeId::of::<T>()) {
+        Some(v) => *v,
+        None => return,
+    };
+
+    for event in input_events.iter().filter(|v| v.request.method == method) {
+        let val: Result<T, String> = params.deserialize(event.request.params.as_ref());
 
         event.matched.store(true, Ordering::SeqCst);
 
+        // events sent by `window.rpc.call` are acknowledged, there is no result
+        let responder = WebviewRpcResponder::<()>::new(
+            event.entity,
+            &event.request,
+            event_transport.webview_action_tx.clone(),
+        );
+
         match val {
             Ok(val) => {
                 log::debug!("Received incoming event method={:?}", event.request.method);
-                output_event_writer.send(WebviewEvent::new(Some(event.entity), val))
+                output_event_writer.send(WebviewEvent::new(Some(event.entity), val));
+                responder.respond(());
             }
 
             Err(e) => {
-                let method = &event.request.method;
                 let type_name = std::any::type_name::<WebviewEvent<T>>();
 
                 log::warn!(
-                    "method={:?} (type={:?}) deserialization error: {}",
+                    "method={:?} (type={:?}, params={:?}) deserialization error: {}",
                     method,
                     type_name,
-                    e.to_string()
+                    params,
+                    e
                 );
+
+                // rejects the call, or is sent to the `_error` listeners of a notification
+                responder.reject(WebviewRpcError::with_code(
+                    WebviewRpcError::INVALID_PARAMS,
+                    format!("{}: {}", method, e),
+                ));
             }
         }
     }
@@ -1,17 +1,30 @@
 use std::sync::atomic::Ordering;
 
-use bevy::{log, prelude::EventReader};
+use bevy::{log, prelude::*};
 
-use crate::events::InputEvent;
+use crate::{events::InputEvent, types::EventTransport, WebviewRpcError, WebviewRpcResponder};
 
-pub(crate) fn rpc_fallthrough_event_logger(mut input_events: EventReader<InputEvent>) {
+pub(crate) fn rpc_fallthrough_event_logger(
+    mut input_events: EventReader<InputEvent>,
+    event_transport: Res<EventTransport>,
+) {
     input_events
         .iter()
         .filter(|v| !v.matched.load(Ordering::SeqCst))
-        .for_each(|event| 
+        .for_each(|event| {
             log::warn!(
-                "Fallthrough event from Javascript. Register it by `.add_webview_input_event`. Requested method: {:?}",
+                "Fallthrough event from Javascript. Register it by `.add_webview_input_event` or `.add_webview_rpc_method`. Requested method: {:?}",
                 event.request.method
+            );
+
+            WebviewRpcResponder::<()>::new(
+                event.entity,
+                &event.request,
+                event_transport.webview_action_tx.clone(),
             )
-        );
+            .reject(WebviewRpcError::with_code(
+                WebviewRpcError::METHOD_NOT_FOUND,
+                format!("Method not found: {}", event.request.method),
+            ));
+        });
 }
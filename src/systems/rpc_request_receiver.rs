@@ -0,0 +1,57 @@
+use std::{any::TypeId, sync::atomic::Ordering};
+
+use bevy::{ecs::system::Resource, log, prelude::*};
+
+use crate::{
+    events::{InputEvent, RpcMethodMapping, WebviewRpcRequest},
+    types::EventTransport,
+    WebviewRpcError, WebviewRpcResponder,
+};
+
+/// Converts `window.rpc.call` requests of the method registered for `Req` into Bevy events
+pub(crate) fn rpc_request_receiver<Req, Resp>(
+    mut input_events: EventReader<InputEvent>,
+    mut request_writer: EventWriter<WebviewRpcRequest<Req, Resp>>,
+    rpc_methods: Res<RpcMethodMapping>,
+    event_transport: Res<EventTransport>,
+) where
+    Req: Resource + for<'de> serde::Deserialize<'de>,
+    Resp: serde::Serialize + 'static,
+{
+    let (method, params) = match rpc_methods.methods.get(&TypeId::of::<Req>()) {
+        Some(v) => *v,
+        None => return,
+    };
+
+    for event in input_events.iter().filter(|v| v.request.method == method) {
+        event.matched.store(true, Ordering::SeqCst);
+
+        let responder = WebviewRpcResponder::<Resp>::new(
+            event.entity,
+            &event.request,
+            event_transport.webview_action_tx.clone(),
+        );
+
+        match params.deserialize(event.request.params.as_ref()) {
+            Ok(params) => {
+                log::debug!("Received RPC request method={:?}", event.request.method);
+                request_writer.send(WebviewRpcRequest { params, responder });
+            }
+
+            Err(e) => {
+                log::warn!(
+                    "RPC method={:?} (type={:?}, params={:?}) deserialization error: {}",
+                    method,
+                    std::any::type_name::<Req>(),
+                    params,
+                    e
+                );
+
+                responder.reject(WebviewRpcError::with_code(
+                    WebviewRpcError::INVALID_PARAMS,
+                    format!("{}: {}", method, e),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{
+    types::{EventTransport, NavigationAction, WebviewAction},
+    WebviewFocus, WebviewSpatialNavigation,
+};
+
+/// Stick (or D-pad axis) deflection that moves the focus
+const AXIS_THRESHOLD: f32 = 0.5;
+
+/// Axis directions held on the last frame, so that holding a stick moves the focus only once
+#[derive(Default)]
+pub struct NavigationState {
+    held: HashMap<(Gamepad, GamepadAxisType), NavigationAction>,
+    /// Gamepads reporting the D-pad as buttons, their `DPadX` / `DPadY` axes mirror the buttons
+    dpad_buttons: HashSet<Gamepad>,
+}
+
+/// Maps gamepad input to spatial navigation of webviews
+pub(crate) fn spatial_navigation_system(
+    mut state: Local<NavigationState>,
+    gamepads: Res<Gamepads>,
+    button_input: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    focus: Res<WebviewFocus>,
+    webview_query: Query<(Entity, &WebviewSpatialNavigation)>,
+    event_transport: Res<EventTransport>,
+) {
+    let mut directions = Vec::new();
+
+    state
+        .dpad_buttons
+        .retain(|gamepad| gamepads.contains(gamepad));
+
+    for gamepad in gamepads.iter() {
+        let mut gamepad_directions = Vec::new();
+
+        for (button_type, direction) in [
+            (GamepadButtonType::DPadUp, NavigationAction::Up),
+            (GamepadButtonType::DPadDown, NavigationAction::Down),
+            (GamepadButtonType::DPadLeft, NavigationAction::Left),
+            (GamepadButtonType::DPadRight, NavigationAction::Right),
+        ] {
+            let button = GamepadButton(*gamepad, button_type);
+
+            if button_input.pressed(button) {
+                state.dpad_buttons.insert(*gamepad);
+            }
+
+            if button_input.just_pressed(button) {
+                gamepad_directions.push(direction);
+            }
+        }
+
+        for (x_axis, y_axis) in [
+            (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            (GamepadAxisType::DPadX, GamepadAxisType::DPadY),
+        ] {
+            // read the D-pad from one source only, or each press would move the focus twice
+            if x_axis == GamepadAxisType::DPadX && state.dpad_buttons.contains(gamepad) {
+                state.held.remove(&(*gamepad, x_axis));
+                continue;
+            }
+
+            let x = axes.get(GamepadAxis(*gamepad, x_axis)).unwrap_or(0.);
+            let y = axes.get(GamepadAxis(*gamepad, y_axis)).unwrap_or(0.);
+
+            let direction = if x.abs() < AXIS_THRESHOLD && y.abs() < AXIS_THRESHOLD {
+                None
+            } else if x.abs() > y.abs() {
+                Some(if x > 0. {
+                    NavigationAction::Right
+                } else {
+                    NavigationAction::Left
+                })
+            } else {
+                Some(if y > 0. {
+                    NavigationAction::Up
+                } else {
+                    NavigationAction::Down
+                })
+            };
+
+            let key = (*gamepad, x_axis);
+            match direction {
+                Some(direction) => {
+                    if state.held.insert(key, direction) != Some(direction)
+                        && !gamepad_directions.contains(&direction)
+                    {
+                        gamepad_directions.push(direction);
+                    }
+                }
+                None => {
+                    state.held.remove(&key);
+                }
+            }
+        }
+
+        directions.extend(gamepad_directions);
+    }
+
+    let targets: Vec<(Entity, &WebviewSpatialNavigation)> = match focus.focused() {
+        Some(focused) => webview_query.get(focused).into_iter().collect(),
+        None => webview_query.iter().collect(),
+    };
+
+    for (entity, navigation) in targets {
+        let mut actions = directions.clone();
+
+        for gamepad in gamepads.iter() {
+            if button_input.just_pressed(GamepadButton(*gamepad, navigation.activate)) {
+                actions.push(NavigationAction::Activate);
+            }
+
+            if button_input.just_pressed(GamepadButton(*gamepad, navigation.back)) {
+                actions.push(NavigationAction::Back);
+            }
+        }
+
+        for action in actions {
+            event_transport
+                .webview_action_tx
+                .send(WebviewAction::Navigate(entity, action))
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use bevy::{input::keyboard::KeyboardInput, prelude::*};
+
+use crate::{
+    input::{key_characters, modifiers_from_input},
+    types::{EventTransport, WebviewAction},
+    WebviewFocus,
+};
+
+/// Maps received characters to webview text input
+///
+/// The characters typed by a key press are sent along with the key by
+/// [`keyboard_event_system`](super::keyboard_event_system), the remaining ones (dead key
+/// compositions, committed IME text) are sent as a single string per frame, to the focused
+/// webview.
+///
+/// IME composition is not forwarded: Bevy 0.7 has no preedit / commit events, so the webview never
+/// sees the text being composed, only the committed characters.
+pub(crate) fn text_input_event_system(
+    event_transport: ResMut<EventTransport>,
+    focus: Res<WebviewFocus>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
+    let (_, text) = key_characters(
+        keyboard_input_events.iter(),
+        received_characters
+            .iter()
+            .map(|received_character| received_character.char),
+        modifiers_from_input(&keyboard_input),
+    );
+
+    if text.is_empty() {
+        return;
+    }
+
+    if let Some(entity) = focus.focused() {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::TypeText((entity, text)))
+            .unwrap();
+    }
+}
@@ -1,9 +1,24 @@
 //! This module is based on bevy_ui, with added positions for Interaction
-use bevy::{core::FloatOrd, input::ElementState, prelude::*, ui::FocusPolicy};
+//!
+//! UI webviews are hit-tested against their node, 3D webviews by casting a ray from the active 3D
+//! camera through the cursor, and sprite webviews the same way from the active 2D camera
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    core::FloatOrd,
+    input::{
+        mouse::{MouseScrollUnit, MouseWheel},
+        ElementState,
+    },
+    prelude::*,
+    render::camera::{ActiveCamera, Camera2d, Camera3d},
+    ui::FocusPolicy,
+};
 
 use crate::{
+    input::modifiers_from_input,
     types::{EventTransport, WebviewAction},
-    Webview,
+    Webview, WebviewFocus, WebviewSize,
 };
 
 #[derive(Component, Copy, Clone, Debug)]
@@ -22,187 +37,531 @@ impl Default for WebviewInteraction {
     }
 }
 
-/// Contains entities whose Interaction should be set to None
+/// Pointer state that persists between frames
 #[derive(Default)]
 pub struct State {
+    /// Entities whose Interaction should be set to None
     entities_to_reset: Vec<Entity>,
+
+    /// Webviews that received the press of a button, and will receive the matching release
+    captured_buttons: HashMap<MouseButton, Vec<Entity>>,
+
+    /// Last known cursor position, used while the cursor is outside of the window
+    cursor_position: Option<Vec2>,
+
+    /// Last motion sent to each webview, unchanged motion is not sent again
+    last_motion: HashMap<Entity, (Vec2, Vec<MouseButton>)>,
+}
+
+impl State {
+    /// Buttons currently held down on the webview
+    fn pressed_buttons(&self, entity: Entity) -> Vec<MouseButton> {
+        self.captured_buttons
+            .iter()
+            .filter(|(_, entities)| entities.contains(&entity))
+            .map(|(button, _)| *button)
+            .collect()
+    }
+}
+
+/// Screen-space bounds of an UI node
+pub(crate) struct NodeBounds {
+    min: Vec2,
+    max: Vec2,
+    clip_min: Vec2,
+    clip_max: Vec2,
+}
+
+impl NodeBounds {
+    pub(crate) fn new(
+        node: &Node,
+        global_transform: &GlobalTransform,
+        clip: Option<&CalculatedClip>,
+    ) -> Self {
+        let ui_position = global_transform.translation.truncate();
+        let extents = node.size / 2.0;
+        let min = ui_position - extents;
+        let max = ui_position + extents;
+
+        let (mut clip_min, mut clip_max) = (min, max);
+        if let Some(clip) = clip {
+            clip_min = Vec2::max(min, clip.clip.min);
+            clip_max = Vec2::min(max, clip.clip.max);
+        }
+
+        Self {
+            min,
+            max,
+            clip_min,
+            clip_max,
+        }
+    }
+
+    /// Whether the position is within the visible (clipped) area of the node
+    pub(crate) fn contains(&self, position: Vec2) -> bool {
+        (self.clip_min.x..self.clip_max.x).contains(&position.x)
+            && (self.clip_min.y..self.clip_max.y).contains(&position.y)
+    }
+
+    /// Position relative to the node, 0..1 when inside of the node
+    pub(crate) fn offset(&self, position: Vec2) -> Vec2 {
+        (position - self.min) / (self.max - self.min)
+    }
+}
+
+/// A ray in world space
+pub(crate) struct Ray {
+    origin: Vec3,
+    direction: Vec3,
+}
+
+impl Ray {
+    /// Ray from the camera through a window position (in logical pixels, origin at bottom left)
+    pub(crate) fn from_camera(
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        window_size: Vec2,
+        position: Vec2,
+    ) -> Option<Self> {
+        if window_size.x <= 0. || window_size.y <= 0. {
+            return None;
+        }
+
+        let ndc = position / window_size * 2.0 - Vec2::ONE;
+        let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix.inverse();
+
+        // bevy uses reversed z: the near plane is at 1, and the far plane (possibly at infinity)
+        // at 0
+        let near = ndc_to_world.project_point3(ndc.extend(1.0));
+        let far = ndc_to_world.project_point3(ndc.extend(0.5));
+
+        let direction = (far - near).try_normalize()?;
+
+        Some(Self {
+            origin: near,
+            direction,
+        })
+    }
+}
+
+/// World-space bounds of a 3D webview quad or a webview sprite
+pub(crate) struct QuadBounds {
+    world_to_local: Mat4,
+    center: Vec3,
+    normal: Vec3,
+    size: Vec2,
+    /// Sprite anchor, see `Anchor::as_vec`
+    anchor: Vec2,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl QuadBounds {
+    pub(crate) fn new(global_transform: &GlobalTransform, webview_size: &WebviewSize) -> Self {
+        Self {
+            world_to_local: global_transform.compute_matrix().inverse(),
+            center: global_transform.translation,
+            normal: global_transform.rotation * Vec3::Z,
+            size: Vec2::new(webview_size.x, webview_size.y),
+            anchor: Vec2::ZERO,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    pub(crate) fn sprite(
+        global_transform: &GlobalTransform,
+        webview_size: &WebviewSize,
+        sprite: &Sprite,
+    ) -> Self {
+        Self {
+            anchor: sprite.anchor.as_vec(),
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
+            ..Self::new(global_transform, webview_size)
+        }
+    }
+
+    /// Distance along the ray to the plane of the quad, and the position relative to the quad (0..1
+    /// when inside of the quad, same as [`NodeBounds::offset`])
+    ///
+    /// `None` when the ray is parallel to the plane
+    pub(crate) fn intersect(&self, ray: &Ray) -> Option<(f32, Vec2)> {
+        let denominator = ray.direction.dot(self.normal);
+        if denominator.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let distance = (self.center - ray.origin).dot(self.normal) / denominator;
+        let hit = ray.origin + ray.direction * distance;
+        let local = self.world_to_local.transform_point3(hit);
+
+        let mut offset = local.truncate() / self.size + self.anchor + Vec2::splat(0.5);
+        if self.flip_x {
+            offset.x = 1. - offset.x;
+        }
+        if self.flip_y {
+            offset.y = 1. - offset.y;
+        }
+
+        Some((distance, offset))
+    }
+
+    /// Whether the ray hits the front side of the quad, in front of the camera
+    pub(crate) fn is_hit(&self, ray: &Ray, distance: f32, offset: Vec2) -> bool {
+        ray.direction.dot(self.normal) < 0.
+            && distance >= 0.
+            && (0.0..1.0).contains(&offset.x)
+            && (0.0..1.0).contains(&offset.y)
+    }
 }
 
 // based on bevy_ui
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn webview_ui_focus_system(
     mut state: Local<State>,
     windows: Res<Windows>,
     mouse_button_input: Res<Input<MouseButton>>,
-    touches_input: Res<Touches>,
-    mut node_query: Query<
+    keyboard_input: Res<Input<KeyCode>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    node_query: Query<
         (
             Entity,
             &Node,
             &GlobalTransform,
-            Option<&mut WebviewInteraction>,
             Option<&FocusPolicy>,
             Option<&CalculatedClip>,
         ),
         With<Webview>,
     >,
+    quad_query: Query<
+        (
+            Entity,
+            &WebviewSize,
+            &GlobalTransform,
+            Option<&FocusPolicy>,
+            Option<&Visibility>,
+        ),
+        (With<Webview>, Without<Node>, Without<Sprite>),
+    >,
+    sprite_query: Query<
+        (
+            Entity,
+            &WebviewSize,
+            &Sprite,
+            &GlobalTransform,
+            Option<&FocusPolicy>,
+            Option<&Visibility>,
+        ),
+        With<Webview>,
+    >,
+    active_camera: Res<ActiveCamera<Camera3d>>,
+    active_camera_2d: Res<ActiveCamera<Camera2d>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut interaction_query: Query<(Entity, &mut WebviewInteraction)>,
+    mut focus: ResMut<WebviewFocus>,
     event_transport: ResMut<EventTransport>,
 ) {
-    let cursor_position = if let Some(cursor_position) = windows
-        .get_primary()
-        .and_then(|window| window.cursor_position())
-    {
-        cursor_position
-    } else {
-        return;
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+
+    if let Some(cursor_position) = window.cursor_position() {
+        state.cursor_position = Some(cursor_position);
+    }
+
+    let cursor_position = match state.cursor_position {
+        Some(cursor_position) => cursor_position,
+        None => return,
     };
 
     // reset entities that were both clicked and released in the last frame
-    for entity in state.entities_to_reset.drain(..) {
-        if let Ok(mut interaction) = node_query.get_component_mut::<Interaction>(entity) {
-            *interaction = Interaction::None;
+    for entity in std::mem::take(&mut state.entities_to_reset) {
+        if let Ok((_, mut interaction)) = interaction_query.get_mut(entity) {
+            *interaction = WebviewInteraction::None;
         }
     }
 
-    let mouse_released =
-        mouse_button_input.just_released(MouseButton::Left) || touches_input.just_released(0);
+    let modifiers = modifiers_from_input(&keyboard_input);
+
+    let just_pressed: Vec<MouseButton> = mouse_button_input.get_just_pressed().copied().collect();
+    let just_released: Vec<MouseButton> = mouse_button_input.get_just_released().copied().collect();
 
-    let _right_released = mouse_button_input.just_released(MouseButton::Right);
+    // sum up the wheel movement of this frame, per unit
+    let (mut line_scroll, mut pixel_scroll) = (Vec2::ZERO, Vec2::ZERO);
+    for mouse_wheel in mouse_wheel_events.iter() {
+        match mouse_wheel.unit {
+            MouseScrollUnit::Line => line_scroll += Vec2::new(mouse_wheel.x, mouse_wheel.y),
+            MouseScrollUnit::Pixel => pixel_scroll += Vec2::new(mouse_wheel.x, mouse_wheel.y),
+        }
+    }
 
-    if mouse_released {
-        for (entity, _node, _global_transform, interaction, _focus_policy, _clip) in
-            node_query.iter_mut()
+    // relative cursor offsets for all nodes, also for the ones that are not hovered (e.g. a drag
+    // continuing outside of the node)
+    let mut offsets: HashMap<Entity, Vec2> = HashMap::new();
+
+    let mut moused_over_z_sorted_nodes = node_query
+        .iter()
+        .filter_map(|(entity, node, global_transform, focus_policy, clip)| {
+            let bounds = NodeBounds::new(node, global_transform, clip);
+            offsets.insert(entity, bounds.offset(cursor_position));
+
+            // if the current cursor position is within the bounds of the node, consider it for
+            // clicking
+            if bounds.contains(cursor_position) {
+                Some((
+                    entity,
+                    focus_policy.cloned(),
+                    FloatOrd(global_transform.translation.z),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    moused_over_z_sorted_nodes.sort_by_key(|(_, _, z)| -*z);
+
+    let window_size = Vec2::new(window.width(), window.height());
+    let cursor_ray = active_camera
+        .get()
+        .and_then(|camera| camera_query.get(camera).ok())
+        .and_then(|(camera, camera_transform)| {
+            Ray::from_camera(camera, camera_transform, window_size, cursor_position)
+        });
+    let cursor_ray_2d = active_camera_2d
+        .get()
+        .and_then(|camera| camera_query.get(camera).ok())
+        .and_then(|(camera, camera_transform)| {
+            Ray::from_camera(camera, camera_transform, window_size, cursor_position)
+        });
+
+    // 3D webviews under the cursor, nearest first. Offsets are also calculated for the ones that
+    // are not hit, as long as the ray crosses their plane
+    let mut moused_over_distance_sorted_quads = Vec::new();
+    if let Some(ray) = cursor_ray.as_ref() {
+        for (entity, webview_size, global_transform, focus_policy, visibility) in quad_query.iter()
         {
-            if let Some(mut interaction) = interaction {
-                if let WebviewInteraction::Clicked(offset) = *interaction {
-                    event_transport
-                        .webview_action_tx
-                        .send(WebviewAction::Click((
-                            entity,
-                            MouseButton::Left,
-                            ElementState::Released,
-                            offset, // FIXME : currently using wrong offset
-                        )))
-                        .unwrap();
+            let bounds = QuadBounds::new(global_transform, webview_size);
+            let (distance, offset) = match bounds.intersect(ray) {
+                Some(intersection) => intersection,
+                None => continue,
+            };
 
-                    *interaction = WebviewInteraction::None;
-                }
+            offsets.insert(entity, offset);
+
+            let is_visible = visibility.map_or(true, |visibility| visibility.is_visible);
+            if is_visible && bounds.is_hit(ray, distance, offset) {
+                moused_over_distance_sorted_quads.push((
+                    entity,
+                    focus_policy.cloned(),
+                    FloatOrd(distance),
+                ));
             }
         }
     }
 
-    let mouse_clicked =
-        mouse_button_input.just_pressed(MouseButton::Left) || touches_input.just_released(0);
+    moused_over_distance_sorted_quads.sort_by_key(|(_, _, distance)| *distance);
 
-    let right_clicked = mouse_button_input.just_pressed(MouseButton::Right);
+    // sprites under the cursor, topmost (highest z) first
+    let mut moused_over_z_sorted_sprites = Vec::new();
+    if let Some(ray) = cursor_ray_2d.as_ref() {
+        for (entity, webview_size, sprite, global_transform, focus_policy, visibility) in
+            sprite_query.iter()
+        {
+            let bounds = QuadBounds::sprite(global_transform, webview_size, sprite);
+            let (distance, offset) = match bounds.intersect(ray) {
+                Some(intersection) => intersection,
+                None => continue,
+            };
 
-    let mut moused_over_z_sorted_nodes = node_query
-        .iter_mut()
-        .filter_map(
-            |(entity, node, global_transform, interaction, focus_policy, clip)| {
-                let position = global_transform.translation;
-                let ui_position = position.truncate();
-                let extents = node.size / 2.0;
-                let mut min = ui_position - extents;
-                let mut max = ui_position + extents;
-                if let Some(clip) = clip {
-                    min = Vec2::max(min, clip.clip.min);
-                    max = Vec2::min(max, clip.clip.max);
-                }
-                // if the current cursor position is within the bounds of the node, consider it for
-                // clicking
-                if (min.x..max.x).contains(&cursor_position.x)
-                    && (min.y..max.y).contains(&cursor_position.y)
-                {
-                    let offset = Vec2::new(
-                        (cursor_position.x - min.x) / (max.x - min.x),
-                        (cursor_position.y - min.y) / (max.y - min.y),
-                    );
-
-                    Some((
-                        entity,
-                        focus_policy,
-                        interaction,
-                        FloatOrd(position.z),
-                        offset,
-                    ))
-                } else {
-                    if let Some(mut interaction) = interaction {
-                        if let WebviewInteraction::Hovered(_) = *interaction {
-                            *interaction = WebviewInteraction::None;
-                        }
-                    }
-                    None
-                }
-            },
-        )
-        .collect::<Vec<_>>();
+            offsets.insert(entity, offset);
+
+            let is_visible = visibility.map_or(true, |visibility| visibility.is_visible);
+            if is_visible && bounds.is_hit(ray, distance, offset) {
+                moused_over_z_sorted_sprites.push((
+                    entity,
+                    focus_policy.cloned(),
+                    FloatOrd(global_transform.translation.z),
+                ));
+            }
+        }
+    }
+
+    moused_over_z_sorted_sprites.sort_by_key(|(_, _, z)| -*z);
+
+    // forget captures of despawned webviews
+    let webview_entities: HashSet<Entity> = node_query
+        .iter()
+        .map(|(entity, ..)| entity)
+        .chain(quad_query.iter().map(|(entity, ..)| entity))
+        .chain(sprite_query.iter().map(|(entity, ..)| entity))
+        .collect();
+
+    for entities in state.captured_buttons.values_mut() {
+        entities.retain(|entity| webview_entities.contains(entity));
+    }
+
+    // top nodes, until a node blocks the ones below it. UI is drawn on top of the 2D sprites, and
+    // those on top of the 3D scene
+    let mut hovered_entities = Vec::new();
+    for (entity, focus_policy, _) in moused_over_z_sorted_nodes
+        .into_iter()
+        .chain(moused_over_z_sorted_sprites)
+        .chain(moused_over_distance_sorted_quads)
+    {
+        hovered_entities.push(entity);
+
+        match focus_policy.unwrap_or(FocusPolicy::Block) {
+            FocusPolicy::Block => {
+                break;
+            }
+            FocusPolicy::Pass => { /* allow the next node to be hovered/clicked */ }
+        }
+    }
+
+    if focus.hovered() != hovered_entities.first().copied() {
+        focus.set_hovered(hovered_entities.first().copied());
+    }
 
-    moused_over_z_sorted_nodes.sort_by_key(|(_, _, _, z, _)| -*z);
-    let mut moused_over_z_sorted_nodes = moused_over_z_sorted_nodes.into_iter();
+    // motion is sent to the hovered nodes, and to the nodes that have captured a button
+    let mut motion_entities = hovered_entities.clone();
+    for entities in state.captured_buttons.values() {
+        for entity in entities {
+            if !motion_entities.contains(entity) {
+                motion_entities.push(*entity);
+            }
+        }
+    }
+
+    state
+        .last_motion
+        .retain(|entity, _| motion_entities.contains(entity));
+
+    for entity in motion_entities.iter() {
+        // the ray of a 3D webview drag may not cross the plane of the webview anymore
+        let offset = match offsets.get(entity) {
+            Some(offset) => *offset,
+            None => continue,
+        };
+
+        let motion = (offset, state.pressed_buttons(*entity));
+        if state.last_motion.get(entity) == Some(&motion) {
+            continue;
+        }
 
-    // set Clicked or Hovered on top nodes
-    for (entity, focus_policy, interaction, _, offset) in moused_over_z_sorted_nodes.by_ref() {
         event_transport
             .webview_action_tx
-            .send(WebviewAction::MouseMotion((entity, offset)))
+            .send(WebviewAction::MouseMotion((
+                *entity,
+                motion.0,
+                motion.1.clone(),
+                modifiers,
+            )))
             .unwrap();
 
-        if let Some(mut interaction) = interaction {
-            if mouse_clicked {
-                event_transport
-                    .webview_action_tx
-                    .send(WebviewAction::Click((
-                        entity,
-                        MouseButton::Left,
-                        ElementState::Pressed,
-                        offset,
-                    )))
-                    .unwrap();
-            }
+        state.last_motion.insert(*entity, motion);
+    }
 
-            if right_clicked {
+    for entity in hovered_entities.iter() {
+        for (unit, scroll) in [
+            (MouseScrollUnit::Line, line_scroll),
+            (MouseScrollUnit::Pixel, pixel_scroll),
+        ] {
+            if scroll != Vec2::ZERO {
                 event_transport
                     .webview_action_tx
-                    .send(WebviewAction::Click((
-                        entity,
-                        MouseButton::Right,
-                        ElementState::Pressed,
-                        offset,
+                    .send(WebviewAction::Scroll((
+                        *entity,
+                        unit,
+                        scroll,
+                        offsets[entity],
+                        modifiers,
                     )))
                     .unwrap();
             }
+        }
+    }
 
-            if mouse_clicked {
-                // only consider nodes with Interaction "clickable"
-                if let WebviewInteraction::Clicked(_) = *interaction {
-                } else {
-                    *interaction = WebviewInteraction::Clicked(offset);
-                    // if the mouse was simultaneously released, reset this Interaction in the next
-                    // frame
-                    if mouse_released {
-                        state.entities_to_reset.push(entity);
-                    }
-                }
-            } else if let WebviewInteraction::None = *interaction {
-                *interaction = WebviewInteraction::Hovered(offset);
-            } else if let WebviewInteraction::Hovered(_) = *interaction {
-                *interaction = WebviewInteraction::Hovered(offset);
-            }
+    // a click focuses the top webview, or blurs when clicking outside of the webviews
+    if !just_pressed.is_empty() && focus.focused() != hovered_entities.first().copied() {
+        match hovered_entities.first() {
+            Some(entity) => focus.focus(*entity),
+            None => focus.blur(),
         }
+    }
 
-        match focus_policy.cloned().unwrap_or(FocusPolicy::Block) {
-            FocusPolicy::Block => {
-                break;
-            }
-            FocusPolicy::Pass => { /* allow the next node to be hovered/clicked */ }
+    // presses go to the hovered nodes, which capture the button until it is released
+    let mut pressed_entities = Vec::new();
+    for button in just_pressed {
+        for entity in hovered_entities.iter() {
+            event_transport
+                .webview_action_tx
+                .send(WebviewAction::Click((
+                    *entity,
+                    button,
+                    ElementState::Pressed,
+                    offsets[entity],
+                    modifiers,
+                )))
+                .unwrap();
+
+            pressed_entities.push(*entity);
         }
+
+        state
+            .captured_buttons
+            .insert(button, hovered_entities.clone());
     }
-    // reset lower nodes to None
-    for (_entity, _focus_policy, interaction, _, _) in moused_over_z_sorted_nodes {
-        if let Some(mut interaction) = interaction {
-            if let WebviewInteraction::None = *interaction {
-                *interaction = WebviewInteraction::None;
+
+    // releases go to the capturing nodes, at the current cursor position
+    let mut released_entities = Vec::new();
+    for button in just_released {
+        for entity in state.captured_buttons.remove(&button).unwrap_or_default() {
+            let offset = match offsets.get(&entity) {
+                Some(offset) => *offset,
+                None => continue,
+            };
+
+            event_transport
+                .webview_action_tx
+                .send(WebviewAction::Click((
+                    entity,
+                    button,
+                    ElementState::Released,
+                    offset,
+                    modifiers,
+                )))
+                .unwrap();
+
+            released_entities.push(entity);
+        }
+    }
+
+    // set Clicked or Hovered on nodes
+    for (entity, mut interaction) in interaction_query.iter_mut() {
+        let offset = match offsets.get(&entity) {
+            Some(offset) => *offset,
+            None => {
+                if !matches!(*interaction, WebviewInteraction::None) {
+                    *interaction = WebviewInteraction::None;
+                }
+                continue;
+            }
+        };
+
+        if pressed_entities.contains(&entity) || !state.pressed_buttons(entity).is_empty() {
+            *interaction = WebviewInteraction::Clicked(offset);
+
+            // if the mouse was simultaneously released, reset this Interaction in the next frame
+            if released_entities.contains(&entity) && state.pressed_buttons(entity).is_empty() {
+                state.entities_to_reset.push(entity);
             }
+        } else if hovered_entities.contains(&entity) {
+            *interaction = WebviewInteraction::Hovered(offset);
+        } else if !matches!(*interaction, WebviewInteraction::None) {
+            *interaction = WebviewInteraction::None;
         }
     }
 }
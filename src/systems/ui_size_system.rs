@@ -1,4 +1,9 @@
-use bevy::{log, prelude::*};
+use bevy::{
+    log,
+    prelude::*,
+    window::{WindowId, WindowScaleFactorChanged},
+};
+use headless_webview::types::WindowSize;
 
 use crate::{
     types::{EventTransport, WebviewAction},
@@ -7,14 +12,34 @@ use crate::{
 
 pub(crate) fn ui_size(
     event_transport: ResMut<EventTransport>,
-    resized_webviews: Query<(Entity, &Node, &Webview), Changed<Node>>,
+    webviews: Query<(Entity, &Node, ChangeTrackers<Node>), With<Webview>>,
+    mut scale_factor_changes: EventReader<WindowScaleFactorChanged>,
+    windows: Res<Windows>,
 ) {
-    for (entity, node, _webview) in resized_webviews.iter() {
-        log::debug!("Webview {:?} resized to {:?}", entity, node.size);
+    // device pixel size of all the webviews changes with the scale factor
+    let scale_factor_changed = scale_factor_changes
+        .iter()
+        .any(|event| event.id == WindowId::primary());
+    let scale_factor = windows.scale_factor(WindowId::primary());
+
+    for (entity, node, node_changes) in webviews.iter() {
+        if !node_changes.is_changed() && !scale_factor_changed {
+            continue;
+        }
+
+        log::debug!(
+            "Webview {:?} resized to {:?}, scale_factor={}",
+            entity,
+            node.size,
+            scale_factor
+        );
 
         event_transport
             .webview_action_tx
-            .send(WebviewAction::Resize((entity, node.size)))
+            .send(WebviewAction::Resize((
+                entity,
+                WindowSize::from_logical(node.size.x, node.size.y, scale_factor),
+            )))
             .unwrap();
     }
 }
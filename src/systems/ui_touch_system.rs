@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use bevy::{
+    core::FloatOrd,
+    input::touch::{Touch, TouchPhase},
+    prelude::*,
+};
+
+use crate::{
+    types::{EventTransport, WebviewAction},
+    Webview, WebviewFocus,
+};
+
+use super::NodeBounds;
+
+/// Touch points that started on a webview, with the last position sent to it
+#[derive(Default)]
+pub struct TouchState {
+    touches: HashMap<u64, (Entity, Vec2)>,
+}
+
+/// Maps bevy touches to webviews
+///
+/// A touch point is sent to the topmost webview it started on, and keeps being sent to it until the
+/// touch ends - even if the finger moves outside of the webview.
+pub(crate) fn webview_ui_touch_system(
+    mut state: Local<TouchState>,
+    touches_input: Res<Touches>,
+    node_query: Query<(Entity, &Node, &GlobalTransform, Option<&CalculatedClip>), With<Webview>>,
+    mut focus: ResMut<WebviewFocus>,
+    event_transport: ResMut<EventTransport>,
+    windows: Res<Windows>,
+) {
+    let window_height = match windows.get_primary() {
+        Some(window) => window.height(),
+        None => return,
+    };
+
+    // UI nodes are laid out bottom-up. `bevy_winit` flips the touch positions on mobile only, on
+    // desktop they are top-down
+    let position = |touch: &Touch| {
+        let position = touch.position();
+
+        if cfg!(any(target_os = "android", target_os = "ios")) {
+            position
+        } else {
+            Vec2::new(position.x, window_height - position.y)
+        }
+    };
+
+    let bounds: HashMap<Entity, (NodeBounds, f32)> = node_query
+        .iter()
+        .map(|(entity, node, global_transform, clip)| {
+            (
+                entity,
+                (
+                    NodeBounds::new(node, global_transform, clip),
+                    global_transform.translation.z,
+                ),
+            )
+        })
+        .collect();
+
+    // forget touches of despawned webviews
+    state
+        .touches
+        .retain(|_, (entity, _)| bounds.contains_key(entity));
+
+    let send = |entity: Entity, touch: &Touch, phase: TouchPhase| {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::Touch((
+                entity,
+                touch.id(),
+                phase,
+                bounds[&entity].0.offset(position(touch)),
+            )))
+            .unwrap();
+    };
+
+    for touch in touches_input.iter_just_pressed() {
+        let entity = bounds
+            .iter()
+            .filter(|(_, (bounds, _))| bounds.contains(position(touch)))
+            .max_by_key(|(_, (_, z))| FloatOrd(*z))
+            .map(|(entity, _)| *entity);
+
+        // a touch focuses the webview, or blurs when touching outside of the webviews
+        if focus.focused() != entity {
+            match entity {
+                Some(entity) => focus.focus(entity),
+                None => focus.blur(),
+            }
+        }
+
+        if let Some(entity) = entity {
+            send(entity, touch, TouchPhase::Started);
+            state.touches.insert(touch.id(), (entity, position(touch)));
+        }
+    }
+
+    for touch in touches_input.iter() {
+        if let Some((entity, last_position)) = state.touches.get_mut(&touch.id()) {
+            if *last_position != position(touch) {
+                *last_position = position(touch);
+                send(*entity, touch, TouchPhase::Moved);
+            }
+        }
+    }
+
+    for touch in touches_input.iter_just_released() {
+        if let Some((entity, _)) = state.touches.remove(&touch.id()) {
+            send(entity, touch, TouchPhase::Ended);
+        }
+    }
+
+    for touch in touches_input.iter_just_cancelled() {
+        if let Some((entity, _)) = state.touches.remove(&touch.id()) {
+            send(entity, touch, TouchPhase::Cancelled);
+        }
+    }
+}
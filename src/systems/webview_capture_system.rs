@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+use crate::{types::EventTransport, WebviewCaptured};
+
+/// Inject webview screenshots as [`WebviewCaptured`] events
+pub(crate) fn webview_capture_event_system(
+    event_transport: Res<EventTransport>,
+    mut capture_events: EventWriter<WebviewCaptured>,
+) {
+    for event in event_transport.capture_rx.try_iter() {
+        capture_events.send(event);
+    }
+}
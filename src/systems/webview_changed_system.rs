@@ -4,15 +4,25 @@ use bevy::{log, prelude::*};
 
 use crate::{
     types::{EventTransport, WebviewAction},
-    Webview, WebviewCommand,
+    Webview, WebviewCommand, WebviewRenderSettings, WebviewState,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn webview_changed_system(
     changed_webviews: Query<(Entity, &Webview), Changed<Webview>>,
     webview_visibility_changes: Query<(Entity, &Visibility), (Changed<Visibility>, With<Webview>)>,
+    render_settings_changes: Query<
+        (Entity, &WebviewRenderSettings),
+        (Changed<WebviewRenderSettings>, With<Webview>),
+    >,
+    alpha_mode_changes: Query<(Entity, &AlphaMode), (Changed<AlphaMode>, With<Webview>)>,
+    alpha_mode_webviews: Query<(Option<&Handle<StandardMaterial>>, &WebviewState), With<Webview>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
     mut previous_webviews: Local<HashMap<Entity, Webview>>,
     event_transport: Res<EventTransport>,
     removed_webviews: RemovedComponents<Webview>,
+    removed_render_settings: RemovedComponents<WebviewRenderSettings>,
+    removed_alpha_modes: RemovedComponents<AlphaMode>,
 ) {
     for (entity, webview) in changed_webviews.iter() {
         let previous = match previous_webviews.get(&entity) {
@@ -76,6 +86,52 @@ pub(crate) fn webview_changed_system(
             .unwrap();
     }
 
+    for (entity, settings) in render_settings_changes.iter() {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::SetRenderSettings(entity, settings.clone()))
+            .unwrap();
+    }
+
+    // back to capturing on every frame
+    for entity in removed_render_settings.iter() {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::SetRenderSettings(
+                entity,
+                WebviewRenderSettings::default(),
+            ))
+            .unwrap();
+    }
+
+    // other webviews have the alpha mode baked into the texture, the whole page is sent again
+    let alpha_modes = alpha_mode_changes
+        .iter()
+        .map(|(entity, alpha_mode)| (entity, *alpha_mode))
+        .chain(
+            removed_alpha_modes
+                .iter()
+                .map(|entity| (entity, AlphaMode::Blend)),
+        );
+
+    for (entity, alpha_mode) in alpha_modes {
+        match alpha_mode_webviews.get(entity) {
+            Ok((Some(material_handle), _)) => {
+                if let Some(material) = standard_materials.get_mut(material_handle) {
+                    material.alpha_mode = alpha_mode;
+                }
+            }
+            // nothing to redo before the first texture
+            Ok((None, state)) if state.texture_added => {
+                event_transport
+                    .webview_action_tx
+                    .send(WebviewAction::SendFullFrame(entity))
+                    .unwrap();
+            }
+            _ => {}
+        }
+    }
+
     for entity in removed_webviews.iter() {
         previous_webviews.remove(&entity);
     }
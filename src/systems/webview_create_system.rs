@@ -2,12 +2,13 @@ use bevy::{
     log,
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    window::WindowId,
 };
 use headless_webview::types::WindowSize;
 
 use crate::{
     types::{EventTransport, LaunchEvent, WebviewAction},
-    Webview, WebviewSize,
+    Webview, WebviewSize, WebviewSpatialNavigation, WebviewTarget,
 };
 
 /// This system takes care of initialing required `PbrBundle` for the webview
@@ -21,6 +22,10 @@ pub(crate) fn create_webview_system(
             &GlobalTransform,
             Option<&Node>,
             Option<&WebviewSize>,
+            Option<&WebviewTarget>,
+            Option<&Sprite>,
+            Option<&AlphaMode>,
+            Option<&WebviewSpatialNavigation>,
         ),
         Added<Webview>,
     >,
@@ -28,12 +33,28 @@ pub(crate) fn create_webview_system(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    windows: Res<Windows>,
 ) {
-    for (entity, webview, transform, global_transform, node, webview_size) in added_webviews.iter()
+    for (
+        entity,
+        webview,
+        transform,
+        global_transform,
+        node,
+        webview_size,
+        target,
+        sprite,
+        alpha_mode,
+        spatial_navigation,
+    ) in added_webviews.iter()
     {
         if let Some(node) = node {
-            let window_size =
-                WindowSize::new(node.size.x.round() as u32, node.size.y.round() as u32);
+            // node size is logical, render the page in device pixels for sharp HiDPI output
+            let window_size = WindowSize::from_logical(
+                node.size.x,
+                node.size.y,
+                windows.scale_factor(WindowId::primary()),
+            );
 
             log::debug!(
                 "Webview {:?} (UI) added, window_size={:?}, texture_size_mb={:.2}",
@@ -50,26 +71,61 @@ pub(crate) fn create_webview_system(
                     entity,
                     webview: webview.clone(),
                     size: window_size.clone(),
+                    spatial_navigation: spatial_navigation.is_some(),
                 }))
                 .unwrap();
 
-            // UI node - insert placeholder image
+            // UI node - show the target image, or insert placeholder image
+            let ui_image = match target {
+                Some(target) => target.0.clone(),
+                None => images.add(Image::new(
+                    Extent3d {
+                        width: window_size.width as u32,
+                        height: window_size.height as u32,
+                        depth_or_array_layers: 1,
+                    },
+                    TextureDimension::D2,
+                    [255, 255, 255, 255]
+                        .repeat(window_size.width as usize * window_size.height as usize),
+                    TextureFormat::Rgba8UnormSrgb,
+                )),
+            };
+
             commands
                 .entity(entity)
-                .insert(UiImage(
-                    images.add(Image::new(
-                        Extent3d {
-                            width: window_size.width as u32,
-                            height: window_size.height as u32,
-                            depth_or_array_layers: 1,
-                        },
-                        TextureDimension::D2,
-                        [255, 255, 255, 255]
-                            .repeat(window_size.width as usize * window_size.height as usize),
-                        TextureFormat::Rgba8Unorm,
-                    )),
-                ))
+                .insert(UiImage(ui_image))
                 .insert(UiColor(webview.color));
+        } else if let (Some(webview_size), Some(sprite)) = (webview_size, sprite) {
+            log::debug!(
+                "Webview {:?} (sprite) added, texture_size_mb={:.2}",
+                entity,
+                webview_size.texture_byte_size_rgba_mb()
+            );
+
+            event_transport
+                .webview_action_tx
+                .send(WebviewAction::Launch(LaunchEvent {
+                    entity,
+                    webview: webview.clone(),
+                    size: WindowSize::new(webview_size.pixels_x(), webview_size.pixels_y()),
+                    spatial_navigation: spatial_navigation.is_some(),
+                }))
+                .unwrap();
+
+            // tinted placeholder until the first texture, resized in place by the texture updates
+            let image = match target {
+                Some(target) => target.0.clone(),
+                None => images.add(Image::default()),
+            };
+
+            commands
+                .entity(entity)
+                .insert(Sprite {
+                    color: webview.color,
+                    custom_size: Some(Vec2::new(webview_size.x, webview_size.y)),
+                    ..sprite.clone()
+                })
+                .insert(image);
         } else if let Some(webview_size) = webview_size {
             log::debug!(
                 "Webview {:?} (PBR) added, texture_size_mb={:.2}",
@@ -83,12 +139,18 @@ pub(crate) fn create_webview_system(
                     entity,
                     webview: webview.clone(),
                     size: WindowSize::new(webview_size.pixels_x(), webview_size.pixels_y()),
+                    spatial_navigation: spatial_navigation.is_some(),
                 }))
                 .unwrap();
 
+            // rendered only into the target image
+            if target.is_some() {
+                continue;
+            }
+
             // PBR node
             let material_handle = materials.add(StandardMaterial {
-                alpha_mode: AlphaMode::Blend,
+                alpha_mode: alpha_mode.copied().unwrap_or(AlphaMode::Blend),
                 unlit: true,
                 base_color: webview.color,
                 ..Default::default()
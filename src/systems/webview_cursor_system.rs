@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{types::EventTransport, Webview, WebviewCursorChanged, WebviewFocus};
+
+/// Inject cursor icon changes from the webviews as [`WebviewCursorChanged`] events
+pub(crate) fn webview_cursor_event_system(
+    event_transport: Res<EventTransport>,
+    mut cursor_events: EventWriter<WebviewCursorChanged>,
+) {
+    for event in event_transport.cursor_rx.try_iter() {
+        cursor_events.send(event);
+    }
+}
+
+/// Cursor icons of the webviews, and the icon applied to the window
+#[derive(Default)]
+pub struct CursorState {
+    icons: HashMap<Entity, CursorIcon>,
+    applied: Option<CursorIcon>,
+}
+
+/// Applies the cursor icon of the topmost hovered webview to the primary window
+///
+/// The default icon is restored when the cursor leaves the webviews.
+pub(crate) fn webview_cursor_icon_system(
+    mut state: Local<CursorState>,
+    mut cursor_events: EventReader<WebviewCursorChanged>,
+    mut windows: ResMut<Windows>,
+    focus: Res<WebviewFocus>,
+    removed_webviews: RemovedComponents<Webview>,
+) {
+    for event in cursor_events.iter() {
+        state.icons.insert(event.entity, event.icon);
+    }
+
+    for entity in removed_webviews.iter() {
+        state.icons.remove(&entity);
+    }
+
+    let hovered_icon = focus.hovered().map(|entity| {
+        state
+            .icons
+            .get(&entity)
+            .copied()
+            .unwrap_or(CursorIcon::Default)
+    });
+
+    let icon = match (hovered_icon, state.applied) {
+        (Some(icon), _) => icon,
+        // restore once the cursor has left the webviews
+        (None, Some(_)) => CursorIcon::Default,
+        (None, None) => return,
+    };
+
+    if state.applied != Some(icon) {
+        if let Some(window) = windows.get_primary_mut() {
+            window.set_cursor_icon(icon);
+        }
+    }
+
+    state.applied = hovered_icon.map(|_| icon);
+}
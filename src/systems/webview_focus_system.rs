@@ -0,0 +1,51 @@
+use bevy::{log, prelude::*};
+
+use crate::{
+    types::{EventTransport, WebviewAction},
+    Webview, WebviewFocus,
+};
+
+/// Forwards keyboard focus changes to the webviews
+pub(crate) fn webview_focus_system(
+    mut focus: ResMut<WebviewFocus>,
+    mut previous_focused: Local<Option<Entity>>,
+    webview_query: Query<(), With<Webview>>,
+    removed_webviews: RemovedComponents<Webview>,
+    event_transport: Res<EventTransport>,
+) {
+    for entity in removed_webviews.iter() {
+        focus.set_editing(entity, false);
+    }
+
+    if let Some(entity) = focus.focused() {
+        if webview_query.get(entity).is_err() {
+            focus.blur();
+        }
+    }
+
+    if !focus.is_changed() || *previous_focused == focus.focused() {
+        return;
+    }
+
+    log::debug!(
+        "Webview focus change from {:?} to {:?}",
+        *previous_focused,
+        focus.focused()
+    );
+
+    if let Some(entity) = *previous_focused {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::SetFocus(entity, false))
+            .unwrap();
+    }
+
+    if let Some(entity) = focus.focused() {
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::SetFocus(entity, true))
+            .unwrap();
+    }
+
+    *previous_focused = focus.focused();
+}
@@ -0,0 +1,66 @@
+use bevy::{log, prelude::*};
+use headless_webview::types::WindowSize;
+
+use crate::{
+    types::{EventTransport, WebviewAction},
+    Webview, WebviewSize,
+};
+
+/// This system resizes 3D and sprite webviews when their [`WebviewSize`] changes
+///
+/// The quad mesh and the sprite size follow the world size right away, the texture is reallocated
+/// when the first frame of the new size is received
+pub(crate) fn webview_size_system(
+    event_transport: ResMut<EventTransport>,
+    mut webviews: Query<
+        (
+            Entity,
+            &WebviewSize,
+            ChangeTrackers<WebviewSize>,
+            Option<&Handle<Mesh>>,
+            Option<&mut Sprite>,
+        ),
+        (With<Webview>, Without<Node>),
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, webview_size, size_changes, mesh_handle, sprite) in webviews.iter_mut() {
+        // new webviews are sized by `create_webview_system`
+        if !size_changes.is_changed() || size_changes.is_added() {
+            continue;
+        }
+
+        if webview_size.pixels_x() == 0 || webview_size.pixels_y() == 0 {
+            log::warn!(
+                "Webview {:?} resized to zero pixels ({:?}) - ignored",
+                entity,
+                webview_size
+            );
+            continue;
+        }
+
+        log::debug!(
+            "Webview {:?} resized to {:?}, texture_size_mb={:.2}",
+            entity,
+            webview_size,
+            webview_size.texture_byte_size_rgba_mb()
+        );
+
+        event_transport
+            .webview_action_tx
+            .send(WebviewAction::Resize((
+                entity,
+                WindowSize::new(webview_size.pixels_x(), webview_size.pixels_y()),
+            )))
+            .unwrap();
+
+        let world_size = Vec2::new(webview_size.x, webview_size.y);
+
+        if let Some(mut sprite) = sprite {
+            sprite.custom_size = Some(world_size);
+        } else if let Some(mesh) = mesh_handle.and_then(|handle| meshes.get_mut(handle)) {
+            // replaced in place, the handle may be shared with the user
+            *mesh = Mesh::from(shape::Quad::new(world_size));
+        }
+    }
+}
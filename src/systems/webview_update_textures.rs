@@ -3,10 +3,10 @@ use std::collections::HashMap;
 use bevy::log;
 use bevy::render::render_resource::{TextureDimension, TextureFormat};
 use bevy::{prelude::*, render::render_resource::Extent3d};
-use headless_webview::types::Texture;
+use headless_webview::types::{Texture, TextureColorSpace, TextureRegion};
 
 use crate::types::{EventTransport, TextureReceivedEvent};
-use crate::{Webview, WebviewState};
+use crate::{Webview, WebviewFrameBuffers, WebviewState, WebviewTarget};
 
 /// This system receives the webview textures, and updates bevy texture accordingly
 ///
@@ -20,6 +20,10 @@ pub(crate) fn update_webview_textures(
             Option<&Node>,
             Option<&mut UiImage>,
             Option<&mut UiColor>,
+            Option<&WebviewTarget>,
+            Option<&mut Sprite>,
+            Option<&Handle<Image>>,
+            Option<&AlphaMode>,
             &mut WebviewState,
         ),
         With<Webview>,
@@ -27,23 +31,88 @@ pub(crate) fn update_webview_textures(
     mut standard_materials: ResMut<Assets<StandardMaterial>>,
     mut images: ResMut<Assets<Image>>,
     mut commands: Commands,
+    frame_buffers: Res<WebviewFrameBuffers>,
 ) {
-    // get only latest per entity
-    let mut texture_events: HashMap<Entity, TextureReceivedEvent> = HashMap::new();
+    // a full texture replaces the earlier ones of the entity, damaged textures are applied in order
+    let mut texture_events: HashMap<Entity, Vec<TextureReceivedEvent>> = HashMap::new();
     for texture_event in event_transport.texture_rx.try_iter() {
-        texture_events.insert(texture_event.entity, texture_event);
+        let entity_events = texture_events.entry(texture_event.entity).or_default();
+
+        if texture_event.texture.region == TextureRegion::Full {
+            for replaced in entity_events.drain(..) {
+                frame_buffers.recycle(replaced.entity, replaced.texture.data);
+            }
+        }
+
+        entity_events.push(texture_event);
     }
 
-    for texture_event in texture_events.values() {
-        let (entity, material_handle, node, ui_image, ui_color, mut webview_state) =
-            match webviews.get_mut(texture_event.entity) {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
+    for mut texture_event in texture_events.into_values().flatten() {
+        let (
+            entity,
+            material_handle,
+            node,
+            ui_image,
+            ui_color,
+            target,
+            sprite,
+            sprite_image,
+            alpha_mode,
+            mut webview_state,
+        ) = match webviews.get_mut(texture_event.entity) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        // only materials have an alpha mode, for the rest it is applied to the texture
+        if material_handle.is_none() {
+            apply_alpha_mode(
+                &mut texture_event.texture,
+                alpha_mode.copied().unwrap_or(AlphaMode::Blend),
+            );
+        }
+
+        // images updated in place: user supplied, or the sprite texture
+        let target_image = match (target, &sprite) {
+            (Some(target), _) => Some(&target.0),
+            (None, Some(_)) => sprite_image,
+            (None, None) => None,
+        };
+
+        match (target_image, material_handle, node, ui_image) {
+            (Some(target_image), _, _, _) => {
+                if images.get(target_image).is_none() {
+                    images.set_untracked(target_image, webview_image(&texture_event.texture));
+                }
+
+                let image = images.get_mut(target_image).unwrap(); // unwrap ok, inserted above
+
+                if !try_apply_webview_texture_to_image(&mut texture_event, image) {
+                    log::debug!(
+                        "Webview {:?} (target) image reallocated (w={}, h={})",
+                        entity,
+                        texture_event.texture.width,
+                        texture_event.texture.height
+                    );
+
+                    // same handle, so everything using the image follows the resize
+                    *image = webview_image(&texture_event.texture);
+                    apply_buffer_to_texture_buffer(&mut texture_event.texture, image);
+                }
+
+                if !webview_state.texture_added {
+                    if let Some(mut ui_color) = ui_color {
+                        ui_color.0 = Color::default();
+                    }
+                    if let Some(mut sprite) = sprite {
+                        sprite.color = Color::default();
+                    }
+                    webview_state.texture_added = true;
+                }
+            }
 
-        match (material_handle, node, ui_image) {
             // PbrBundle
-            (Some(material_handle), None, None) => {
+            (None, Some(material_handle), None, None) => {
                 let material = match standard_materials.get_mut(material_handle) {
                     Some(v) => v,
                     None => continue,
@@ -58,7 +127,7 @@ pub(crate) fn update_webview_textures(
 
                 if let Some(image) = image {
                     if webview_state.texture_added
-                        && try_apply_webview_texture_to_image(&texture_event, image)
+                        && try_apply_webview_texture_to_image(&mut texture_event, image)
                     {
                         needs_new_texture = false;
                     }
@@ -76,23 +145,9 @@ pub(crate) fn update_webview_textures(
                 if needs_new_texture || !webview_state.texture_added {
                     let mut material = standard_materials.get_mut(material_handle).unwrap();
 
-                    let mut new_image = Image::new(
-                        Extent3d {
-                            width: texture_event.texture.width as u32,
-                            height: texture_event.texture.height as u32,
-                            depth_or_array_layers: 1,
-                        },
-                        TextureDimension::D2,
-                        vec![
-                            0u8;
-                            texture_event.texture.width as usize
-                                * texture_event.texture.height as usize
-                                * 4
-                        ],
-                        TextureFormat::Rgba8UnormSrgb,
-                    );
+                    let mut new_image = webview_image(&texture_event.texture);
 
-                    apply_buffer_to_texture_buffer(&texture_event.texture, &mut new_image);
+                    apply_buffer_to_texture_buffer(&mut texture_event.texture, &mut new_image);
 
                     if let Some(base_color_texture) = material.base_color_texture.as_ref() {
                         images.remove(base_color_texture);
@@ -111,7 +166,7 @@ pub(crate) fn update_webview_textures(
             }
 
             // UI bundle
-            (None, Some(_ui_node), mut ui_image) => {
+            (None, None, Some(_ui_node), mut ui_image) => {
                 let image = match &ui_image {
                     Some(ui_image) => images.get_mut(&ui_image.0),
                     None => None,
@@ -121,7 +176,7 @@ pub(crate) fn update_webview_textures(
 
                 if let Some(image) = image {
                     if webview_state.texture_added
-                        && try_apply_webview_texture_to_image(&texture_event, image)
+                        && try_apply_webview_texture_to_image(&mut texture_event, image)
                     {
                         needs_new_texture = false;
                     } else {
@@ -140,23 +195,9 @@ pub(crate) fn update_webview_textures(
 
                 /////// ABSTRACT //////
                 if needs_new_texture {
-                    let mut new_image = Image::new(
-                        Extent3d {
-                            width: texture_event.texture.width as u32,
-                            height: texture_event.texture.height as u32,
-                            depth_or_array_layers: 1,
-                        },
-                        TextureDimension::D2,
-                        vec![
-                            0u8;
-                            texture_event.texture.width as usize
-                                * texture_event.texture.height as usize
-                                * 4
-                        ],
-                        TextureFormat::Rgba8UnormSrgb,
-                    );
+                    let mut new_image = webview_image(&texture_event.texture);
 
-                    apply_buffer_to_texture_buffer(&texture_event.texture, &mut new_image);
+                    apply_buffer_to_texture_buffer(&mut texture_event.texture, &mut new_image);
 
                     let image_handle = images.add(new_image);
 
@@ -181,54 +222,99 @@ pub(crate) fn update_webview_textures(
                 log::warn!("Unknown webview texture combination");
             }
         };
+
+        frame_buffers.recycle(texture_event.entity, texture_event.texture.data);
+    }
+}
+
+/// Empty image matching the size of the texture
+fn webview_image(texture: &Texture) -> Image {
+    Image::new(
+        Extent3d {
+            width: texture.width as u32,
+            height: texture.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        vec![0u8; texture.width as usize * texture.height as usize * 4],
+        image_format(texture),
+    )
+}
+
+/// Image format matching the color space of the texture
+fn image_format(texture: &Texture) -> TextureFormat {
+    match texture.color_space {
+        TextureColorSpace::Srgb => TextureFormat::Rgba8UnormSrgb,
+        TextureColorSpace::Linear => TextureFormat::Rgba8Unorm,
+    }
+}
+
+/// Bake the alpha mode into the texture alpha, for the images that are blended as is
+fn apply_alpha_mode(texture: &mut Texture, alpha_mode: AlphaMode) {
+    if texture.format != headless_webview::types::TextureFormat::Rgba8 {
+        return;
+    }
+
+    let alpha = |a: u8| match alpha_mode {
+        AlphaMode::Opaque => 255,
+        AlphaMode::Mask(cutoff) if a as f32 / 255. >= cutoff => 255,
+        AlphaMode::Mask(_) => 0,
+        AlphaMode::Blend => a,
+    };
+
+    if alpha_mode != AlphaMode::Blend {
+        for pixel in texture.data.chunks_exact_mut(4) {
+            pixel[3] = alpha(pixel[3]);
+        }
     }
 }
 
 fn try_apply_webview_texture_to_image(
-    webview_texture_event: &TextureReceivedEvent,
+    webview_texture_event: &mut TextureReceivedEvent,
     image: &mut Image,
 ) -> bool {
     if webview_texture_event.texture.width as u32 != image.texture_descriptor.size.width
         || webview_texture_event.texture.height as u32 != image.texture_descriptor.size.height
+        || image_format(&webview_texture_event.texture) != image.texture_descriptor.format
     {
         return false;
     }
 
-    apply_buffer_to_texture_buffer(&webview_texture_event.texture, image)
+    apply_buffer_to_texture_buffer(&mut webview_texture_event.texture, image)
 }
 
-fn apply_buffer_to_texture_buffer(texture: &Texture, image: &mut Image) -> bool {
-    match (&texture.format, &image.texture_descriptor.format) {
-        (headless_webview::types::TextureFormat::Rgb8, TextureFormat::Rgba8UnormSrgb) => {
-            // TODO can this be a performance bottleneck - par-iter?
-            for (val, other) in image.data.chunks_mut(4).zip(texture.data.chunks(3)) {
-                val[0] = other[0];
-                val[1] = other[1];
-                val[2] = other[2];
-                val[3] = 255;
-            }
+/// Apply the texture to the image. The texture data may be swapped with the previous image data
+fn apply_buffer_to_texture_buffer(texture: &mut Texture, image: &mut Image) -> bool {
+    if image_format(texture) != image.texture_descriptor.format {
+        log::warn!(
+            "Unknown combination of webview textures: {:?} {:?} {:?}",
+            texture.format,
+            texture.color_space,
+            image.texture_descriptor.format
+        );
 
-            true
-        }
+        return false;
+    }
 
-        (headless_webview::types::TextureFormat::Rgba8, TextureFormat::Rgba8UnormSrgb) => {
-            if image.data.len() == texture.data.len() {
-                image.data.copy_from_slice(&texture.data);
-            } else {
-                log::error!("Webview update failed, mismatched texture dimensions!");
-            }
+    if texture.width != image.texture_descriptor.size.width
+        || texture.height != image.texture_descriptor.size.height
+        || texture.data.len() != texture.buffer_size()
+    {
+        log::error!("Webview update failed, mismatched texture dimensions!");
+        return true;
+    }
 
-            true
-        }
+    // full frames are swapped in without copying, the previous image data becomes the next buffer
+    if texture.format.n_channels() == 4
+        && texture.region == TextureRegion::Full
+        && image.data.len() == texture.data.len()
+    {
+        std::mem::swap(&mut image.data, &mut texture.data);
+        return true;
+    }
 
-        _ => {
-            log::warn!(
-                "Unknown combination of webview textures: {:?} {:?}",
-                texture.format,
-                image.texture_descriptor.format
-            );
+    // only the updated rectangles are copied, row by row
+    texture.copy_rects_into(&mut image.data, 4);
 
-            false
-        }
-    }
+    true
 }
@@ -1,32 +1,49 @@
 use bevy::{
-    input::{keyboard::KeyboardInput, ElementState},
+    input::{keyboard::KeyboardInput, mouse::MouseScrollUnit, touch::TouchPhase, ElementState},
     prelude::*,
 };
 use crossbeam_channel::{Receiver, Sender};
-use headless_webview::types::{Texture, WindowSize};
+use headless_webview::{
+    types::{Modifiers, Texture, WindowSize},
+    webview::RpcResponse,
+};
 
-use crate::{events::InputEvent, Webview, WebviewCommand};
+use crate::{
+    events::InputEvent, Webview, WebviewCaptured, WebviewCommand, WebviewCursorChanged,
+    WebviewFrameBuffers, WebviewRenderSettings,
+};
 
 #[derive(Debug)]
 pub(crate) enum WebviewAction {
-    /// Webview RPC on javascript-side was initialized
-    SetRPCInitialized(Entity),
+    /// Webview RPC on javascript-side was initialized, or the page is unloading
+    SetRPCInitialized(Entity, bool),
     /// Launch a new webview (open a web page in a window)
     Launch(LaunchEvent),
-    /// Mouse motion over webview
-    MouseMotion((Entity, Vec2)),
+    /// Mouse motion over webview, with the buttons held down on it
+    MouseMotion((Entity, Vec2, Vec<MouseButton>, Modifiers)),
     /// Webview was clicked
-    Click((Entity, MouseButton, ElementState, Vec2)),
+    Click((Entity, MouseButton, ElementState, Vec2, Modifiers)),
+    /// Webview was scrolled: unit, scroll amount (as in Bevy `MouseWheel`) and position
+    Scroll((Entity, MouseScrollUnit, Vec2, Vec2, Modifiers)),
+    /// Touch point on webview: touch id, phase and position
+    Touch((Entity, u64, TouchPhase, Vec2)),
     /// Webview was hovered
     Hover((Entity, Vec2)),
-    /// Webview received keyboard input
-    TypeKeyboard((Entity, KeyboardInput)),
-    /// Webview should be resized
-    Resize((Entity, Vec2)),
+    /// Webview received keyboard input, with the modifier keys held at the time, and the character
+    /// typed by the key
+    TypeKeyboard((Entity, KeyboardInput, Modifiers, Option<char>)),
+    /// Webview received text input that was not typed by a forwarded key
+    TypeText((Entity, String)),
+    /// Webview should be resized, in device pixels
+    Resize((Entity, WindowSize)),
     /// Webview should be deleted
     Remove(Entity),
     /// Events to webview(s)
     SendOutputEvent(Option<Entity>, String, String),
+    /// Binary events to webview(s), received as an `ArrayBuffer`
+    SendBinaryEvent(Option<Entity>, String, Vec<u8>),
+    /// Settle the promise of a `window.rpc.call`
+    SendRpcResponse(Entity, RpcResponse),
     /// Tick webviews once (run event loop)
     Tick,
     /// AppExit event handling
@@ -35,6 +52,39 @@ pub(crate) enum WebviewAction {
     RunCommand(Option<Entity>, WebviewCommand),
     /// Visibility changes
     SetVisibility(Entity, bool),
+    /// Keyboard focus changes
+    SetFocus(Entity, bool),
+    /// Spatial navigation (gamepad) input
+    Navigate(Entity, NavigationAction),
+    /// Capture rate / mode changes
+    SetRenderSettings(Entity, WebviewRenderSettings),
+    /// Send the whole page as a full texture, e.g. to bake a changed alpha mode into the image
+    SendFullFrame(Entity),
+}
+
+/// Spatial navigation input, see `spatial_navigation.js`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NavigationAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Activate,
+    Back,
+}
+
+impl NavigationAction {
+    /// Javascript calling the spatial navigation helper
+    pub fn script(&self) -> &'static str {
+        match self {
+            NavigationAction::Up => "window.spatialNavigation.move(\"up\");",
+            NavigationAction::Down => "window.spatialNavigation.move(\"down\");",
+            NavigationAction::Left => "window.spatialNavigation.move(\"left\");",
+            NavigationAction::Right => "window.spatialNavigation.move(\"right\");",
+            NavigationAction::Activate => "window.spatialNavigation.activate();",
+            NavigationAction::Back => "window.spatialNavigation.back();",
+        }
+    }
 }
 
 /// Webview launch data
@@ -43,6 +93,8 @@ pub(crate) struct LaunchEvent {
     pub entity: Entity,
     pub webview: Webview,
     pub size: WindowSize,
+    /// Entity has [`crate::WebviewSpatialNavigation`]
+    pub spatial_navigation: bool,
 }
 
 /// Texture from webview
@@ -57,4 +109,7 @@ pub(crate) struct EventTransport {
     pub webview_action_tx: Sender<WebviewAction>,
     pub texture_rx: Receiver<TextureReceivedEvent>,
     pub input_event_rx: Receiver<InputEvent>,
+    pub cursor_rx: Receiver<WebviewCursorChanged>,
+    pub capture_rx: Receiver<WebviewCaptured>,
+    pub frame_buffers: WebviewFrameBuffers,
 }
@@ -0,0 +1,679 @@
+//! TypeScript definitions of the registered webview events and RPC methods, generated from the
+//! JSON schemas of the payload types (`schemars::JsonSchema`)
+use std::any::{type_name, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use bevy::{log, prelude::*};
+use serde_json::Value;
+
+use crate::events::{
+    BinaryEventMapping, InputEventMapping, OutputEventMapping, RpcMethodMapping, WebviewRpcParams,
+};
+
+/// Trait that extends a Bevy [`App`] for registering the JSON schemas of webview payload types,
+/// used by [`typescript_definitions`]
+///
+/// Schemas are opt-in, events and RPC methods registered without one are typed `unknown`.
+pub trait WebviewSchemaApp {
+    /// Register the schema of an input or output event payload, see
+    /// [`crate::WebviewApp::add_webview_input_event`]
+    fn add_webview_event_schema<T>(&mut self) -> &mut Self
+    where
+        T: schemars::JsonSchema + 'static;
+
+    /// Register the schemas of the request and the response of an RPC method, see
+    /// [`crate::WebviewApp::add_webview_rpc_method`]
+    fn add_webview_rpc_method_schema<Req, Resp>(&mut self) -> &mut Self
+    where
+        Req: schemars::JsonSchema + 'static,
+        Resp: schemars::JsonSchema + 'static;
+}
+
+impl WebviewSchemaApp for App {
+    fn add_webview_event_schema<T>(&mut self) -> &mut Self
+    where
+        T: schemars::JsonSchema + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(WebviewSchemas::default)
+            .insert::<T>();
+        self
+    }
+
+    fn add_webview_rpc_method_schema<Req, Resp>(&mut self) -> &mut Self
+    where
+        Req: schemars::JsonSchema + 'static,
+        Resp: schemars::JsonSchema + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(WebviewSchemas::default)
+            .insert_rpc_method::<Req, Resp>();
+        self
+    }
+}
+
+/// JSON schemas of the registered payload types
+#[derive(Default)]
+pub(crate) struct WebviewSchemas {
+    schemas: HashMap<TypeId, PayloadSchema>,
+    /// Response type of the RPC methods, by the request type
+    rpc_responses: HashMap<TypeId, TypeId>,
+}
+
+struct PayloadSchema {
+    /// Rust type name without the module path, `None` for generic and primitive types
+    rust_name: Option<&'static str>,
+    schema: Value,
+}
+
+impl WebviewSchemas {
+    pub fn insert<T: schemars::JsonSchema + 'static>(&mut self) {
+        let rust_name = Some(type_name::<T>())
+            .filter(|name| !name.contains(['<', '(', '[', '&']))
+            .and_then(|name| name.rsplit("::").next());
+
+        // unwrap ok, the schema is plain JSON
+        let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap();
+
+        self.schemas
+            .insert(TypeId::of::<T>(), PayloadSchema { rust_name, schema });
+    }
+
+    pub fn insert_rpc_method<Req, Resp>(&mut self)
+    where
+        Req: schemars::JsonSchema + 'static,
+        Resp: schemars::JsonSchema + 'static,
+    {
+        self.insert::<Req>();
+        self.insert::<Resp>();
+        self.rpc_responses
+            .insert(TypeId::of::<Req>(), TypeId::of::<Resp>());
+    }
+}
+
+/// File written by [`write_typescript_definitions`]
+pub(crate) struct TypescriptDefinitionsPath(pub PathBuf);
+
+/// Startup system writing the definitions, once all the events have been registered
+pub(crate) fn write_typescript_definitions(world: &World) {
+    let path = match world.get_resource::<TypescriptDefinitionsPath>() {
+        Some(path) => &path.0,
+        None => return,
+    };
+
+    let definitions = typescript_definitions(world);
+
+    // not touched when unchanged, so that the frontend dev server does not rebuild on every launch
+    if std::fs::read_to_string(path).ok().as_deref() == Some(definitions.as_str()) {
+        return;
+    }
+
+    match std::fs::write(path, definitions) {
+        Ok(()) => log::info!("Webview TypeScript definitions written to {:?}", path),
+        Err(e) => log::warn!(
+            "Could not write webview TypeScript definitions to {:?}: {}",
+            path,
+            e
+        ),
+    }
+}
+
+/// TypeScript definitions (`.d.ts`) typing `window.rpc.notify`, `window.rpc.call` and
+/// `window.rpc.on` for the events and RPC methods registered with [`crate::WebviewApp`]
+///
+/// Methods starting with `_` are internal, and left out.
+pub fn typescript_definitions(world: &World) -> String {
+    let no_schemas = WebviewSchemas::default();
+    let schemas = world
+        .get_resource::<WebviewSchemas>()
+        .unwrap_or(&no_schemas);
+
+    let mut types = TypeDefinitions::default();
+    let mut payload = |type_id: &TypeId| types.payload(schemas.schemas.get(type_id));
+    // a missing first argument is read as `null`, unknown payloads may accept it as well
+    let optional = |type_id: &TypeId| match schemas.schemas.get(type_id) {
+        Some(payload) => accepts_null(&payload.schema),
+        None => true,
+    };
+
+    let mut input_events = BTreeMap::new();
+    if let Some(mapping) = world.get_resource::<InputEventMapping>() {
+        for (type_id, (method, params)) in mapping.events.iter().filter(is_public) {
+            input_events.insert(
+                *method,
+                arguments(*params, payload(type_id), optional(type_id)),
+            );
+        }
+    }
+
+    let mut rpc_methods = BTreeMap::new();
+    if let Some(mapping) = world.get_resource::<RpcMethodMapping>() {
+        for (type_id, (method, params)) in mapping.methods.iter().filter(is_public) {
+            let result = match schemas.rpc_responses.get(type_id) {
+                Some(response_type_id) => payload(response_type_id),
+                None => String::from("unknown"),
+            };
+
+            rpc_methods.insert(
+                *method,
+                format!(
+                    "{{ params: {}; result: {} }}",
+                    arguments(*params, payload(type_id), optional(type_id)),
+                    result
+                ),
+            );
+        }
+    }
+
+    let mut output_events = BTreeMap::new();
+    if let Some(mapping) = world.get_resource::<OutputEventMapping>() {
+        for (type_id, method) in mapping
+            .events
+            .iter()
+            .filter(|(_, method)| !method.starts_with('_'))
+        {
+            output_events.insert(*method, payload(type_id));
+        }
+    }
+
+    let mut binary_input_events = BTreeMap::new();
+    if let Some(mapping) = world.get_resource::<BinaryEventMapping>() {
+        for method in mapping.input_events.values() {
+            binary_input_events.insert(*method, String::from("ArrayBuffer | ArrayBufferView"));
+        }
+
+        for method in mapping.output_events.values() {
+            output_events.insert(*method, String::from("ArrayBuffer"));
+        }
+    }
+
+    let mut ts = String::from(
+        "// Generated by bevy_webview from the registered webview events, do not edit\n\n",
+    );
+
+    for declaration in types.types.values() {
+        ts.push_str(declaration);
+        ts.push_str("\n\n");
+    }
+
+    write_interface(
+        &mut ts,
+        "Input events, `window.rpc.notify(method, ...params)`",
+        "WebviewInputEvents",
+        &input_events,
+    );
+    write_interface(
+        &mut ts,
+        "RPC methods, `window.rpc.call(method, ...params)`",
+        "WebviewRpcMethods",
+        &rpc_methods,
+    );
+    write_interface(
+        &mut ts,
+        "Binary input events, `window.rpc.notifyBinary(method, data)`",
+        "WebviewBinaryInputEvents",
+        &binary_input_events,
+    );
+    write_interface(
+        &mut ts,
+        "Output events, `window.rpc.on(method, callback)`",
+        "WebviewOutputEvents",
+        &output_events,
+    );
+
+    ts.push_str(RPC_DEFINITIONS);
+    ts
+}
+
+/// The `window.rpc` client, see `rpc.js`
+const RPC_DEFINITIONS: &str = r#"/** Rejection value of `window.rpc.call`, and the payload of `_error` events */
+export interface WebviewRpcError {
+  code: number;
+  message: string;
+  data?: unknown;
+}
+
+export interface WebviewRpc {
+  /** Milliseconds to wait for the reply of a `call`, 0 waits forever */
+  timeout: number;
+  call<M extends keyof WebviewRpcMethods>(method: M, ...params: WebviewRpcMethods[M]["params"]): Promise<WebviewRpcMethods[M]["result"]>;
+  call<M extends keyof WebviewInputEvents>(method: M, ...params: WebviewInputEvents[M]): Promise<null>;
+  notify<M extends keyof WebviewInputEvents>(method: M, ...params: WebviewInputEvents[M]): Promise<void>;
+  notifyBinary<M extends keyof WebviewBinaryInputEvents>(method: M, data: WebviewBinaryInputEvents[M]): Promise<void>;
+  on<M extends keyof WebviewOutputEvents>(method: M, callback: (payload: WebviewOutputEvents[M]) => void): void;
+  on(method: "_error", callback: (error: WebviewRpcError) => void): void;
+  removeListener<M extends keyof WebviewOutputEvents>(method: M, callback: (payload: WebviewOutputEvents[M]) => void): void;
+}
+
+declare global {
+  interface Window {
+    rpc: WebviewRpc;
+  }
+}
+"#;
+
+fn write_interface(ts: &mut String, doc: &str, name: &str, members: &BTreeMap<&str, String>) {
+    // unwraps ok, writing into a string
+    writeln!(ts, "/** {} */", doc).unwrap();
+    writeln!(ts, "export interface {} {{", name).unwrap();
+
+    for (method, member) in members.iter() {
+        writeln!(ts, "  {}: {};", Value::from(*method), member).unwrap();
+    }
+
+    ts.push_str("}\n\n");
+}
+
+fn is_public<T>((_, (method, _)): &(T, &(&str, WebviewRpcParams))) -> bool {
+    !method.starts_with('_')
+}
+
+/// Rest parameters of `notify` / `call`, see [`WebviewRpcParams`]
+///
+/// An `optional` first argument may be left out, e.g. `rpc.notify("close")` for a `()` payload
+fn arguments(params: WebviewRpcParams, payload: String, optional: bool) -> String {
+    match params {
+        WebviewRpcParams::First if optional => format!("[payload?: {}]", payload),
+        WebviewRpcParams::First => format!("[payload: {}]", payload),
+        WebviewRpcParams::Named => format!("[params: {}]", payload),
+        // a tuple type
+        WebviewRpcParams::Tuple => payload,
+        WebviewRpcParams::Unit => String::from("[]"),
+    }
+}
+
+/// Named types of the payloads, `export type Name = ...;` by name
+#[derive(Default)]
+struct TypeDefinitions {
+    types: BTreeMap<String, String>,
+}
+
+impl TypeDefinitions {
+    /// TypeScript type of a payload, its named types are added to the definitions
+    fn payload(&mut self, payload: Option<&PayloadSchema>) -> String {
+        let payload = match payload {
+            Some(payload) => payload,
+            None => return String::from("unknown"),
+        };
+
+        if let Some(definitions) = payload.schema.get("definitions").and_then(Value::as_object) {
+            for (name, schema) in definitions {
+                self.define(name, schema);
+            }
+        }
+
+        match payload.rust_name {
+            Some(name) if is_named(&payload.schema, name) => {
+                self.define(name, &payload.schema);
+                identifier(name)
+            }
+            _ => ts_type(&payload.schema),
+        }
+    }
+
+    fn define(&mut self, name: &str, schema: &Value) {
+        let name = identifier(name);
+        let mut declaration = String::new();
+
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            declaration.push_str("/**\n");
+            for line in description.lines() {
+                // unwrap ok, writing into a string
+                writeln!(declaration, " * {}", line.replace("*/", "*\\/")).unwrap();
+            }
+            declaration.push_str(" */\n");
+        }
+
+        // unwrap ok, writing into a string
+        write!(declaration, "export type {} = {};", name, ts_type(schema)).unwrap();
+
+        if let Some(previous) = self.types.insert(name.clone(), declaration) {
+            if previous != self.types[&name] {
+                log::warn!(
+                    "Webview TypeScript definitions: several types are named {:?}",
+                    name
+                );
+            }
+        }
+    }
+}
+
+/// User types (structs, enums) are declared by name, primitives and collections inlined
+fn is_named(schema: &Value, rust_name: &str) -> bool {
+    let primitive = matches!(
+        schema.get("type").and_then(Value::as_str),
+        Some("string" | "number" | "integer" | "boolean" | "null")
+    );
+    let enumeration = ["enum", "oneOf", "anyOf"]
+        .iter()
+        .any(|keyword| schema.get(keyword).is_some());
+
+    schema.get("title").and_then(Value::as_str) == Some(rust_name) && (!primitive || enumeration)
+}
+
+/// Whether `null` is valid for the JSON schema, references are not followed
+fn accepts_null(schema: &Value) -> bool {
+    let schema = match schema {
+        Value::Bool(accepts) => return *accepts,
+        Value::Object(schema) => schema,
+        _ => return false,
+    };
+
+    if let Some(value) = schema.get("const") {
+        return value.is_null();
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values.contains(&Value::Null);
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+            return schemas.iter().any(accepts_null);
+        }
+    }
+
+    match schema.get("type") {
+        Some(Value::String(instance_type)) => instance_type == "null",
+        Some(Value::Array(instance_types)) => instance_types.iter().any(|t| t == "null"),
+        _ => false,
+    }
+}
+
+/// TypeScript type of a JSON schema
+fn ts_type(schema: &Value) -> String {
+    let schema = match schema {
+        Value::Bool(false) => return String::from("never"),
+        Value::Object(schema) => schema,
+        _ => return String::from("unknown"),
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return identifier(reference.rsplit('/').next().unwrap_or(reference));
+    }
+
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return join(values.iter().map(Value::to_string), " | ");
+    }
+
+    for (keyword, separator) in [("anyOf", " | "), ("oneOf", " | "), ("allOf", " & ")] {
+        if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+            return join(schemas.iter().map(ts_type), separator);
+        }
+    }
+
+    match schema.get("type") {
+        Some(Value::String(instance_type)) => ts_instance_type(instance_type, schema),
+        Some(Value::Array(instance_types)) => join(
+            instance_types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|instance_type| ts_instance_type(instance_type, schema)),
+            " | ",
+        ),
+        _ => String::from("unknown"),
+    }
+}
+
+fn ts_instance_type(instance_type: &str, schema: &serde_json::Map<String, Value>) -> String {
+    match instance_type {
+        "null" => String::from("null"),
+        "boolean" => String::from("boolean"),
+        "integer" | "number" => String::from("number"),
+        "string" => String::from("string"),
+        "array" => match schema.get("items") {
+            // tuple
+            Some(Value::Array(items)) => format!(
+                "[{}]",
+                items.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+            ),
+            Some(items) => format!("{}[]", group(ts_type(items))),
+            None => String::from("unknown[]"),
+        },
+        "object" => ts_object_type(schema),
+        _ => String::from("unknown"),
+    }
+}
+
+fn ts_object_type(schema: &serde_json::Map<String, Value>) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut members = Vec::new();
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (name, property) in properties {
+            let optional = if required.contains(&name.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+
+            members.push(format!(
+                "{}{}: {}",
+                property_name(name),
+                optional,
+                ts_type(property)
+            ));
+        }
+    }
+
+    // maps, or objects without a known shape
+    match schema.get("additionalProperties") {
+        Some(Value::Bool(false)) => {}
+        Some(value @ Value::Object(_)) => {
+            members.push(format!("[key: string]: {}", ts_type(value)));
+        }
+        _ if !schema.contains_key("properties") => {
+            members.push(String::from("[key: string]: unknown"));
+        }
+        _ => {}
+    }
+
+    if members.is_empty() {
+        String::from("{}")
+    } else {
+        format!("{{ {} }}", members.join("; "))
+    }
+}
+
+/// Union or intersection of the types
+fn join(types: impl Iterator<Item = String>, separator: &str) -> String {
+    let mut unique: Vec<String> = Vec::new();
+    for ts in types.map(group) {
+        if !unique.contains(&ts) {
+            unique.push(ts);
+        }
+    }
+
+    match unique.len() {
+        0 => String::from("never"),
+        _ => unique.join(separator),
+    }
+}
+
+/// Parenthesize unions and intersections, for nesting them into other types
+fn group(ts: String) -> String {
+    if ts.contains(" | ") || ts.contains(" & ") {
+        format!("({})", ts)
+    } else {
+        ts
+    }
+}
+
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if valid {
+        String::from(name)
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // `schema_for!` outputs of schemars 0.8, without `$schema`
+
+    /// `struct LoginRequest { username: String, remember: Option<bool> }`
+    fn login_request() -> Value {
+        json!({
+            "title": "LoginRequest",
+            "type": "object",
+            "required": ["username"],
+            "properties": {
+                "username": { "type": "string" },
+                "remember": { "type": ["boolean", "null"] }
+            }
+        })
+    }
+
+    /// `enum Direction { Up, Down }`
+    fn direction() -> Value {
+        json!({ "title": "Direction", "type": "string", "enum": ["Up", "Down"] })
+    }
+
+    /// `()`
+    fn unit() -> Value {
+        json!({ "title": "Null", "type": "null" })
+    }
+
+    #[test]
+    fn test_ts_type() {
+        assert_eq!(
+            ts_type(&login_request()),
+            "{ remember?: boolean | null; username: string }"
+        );
+        assert_eq!(ts_type(&direction()), r#""Up" | "Down""#);
+        assert_eq!(ts_type(&unit()), "null");
+
+        // `Option<String>`
+        assert_eq!(
+            ts_type(&json!({ "title": "Nullable_String", "type": ["string", "null"] })),
+            "string | null"
+        );
+
+        // `Vec<Option<u32>>`
+        assert_eq!(
+            ts_type(&json!({
+                "title": "Array_of_Nullable_uint32",
+                "type": "array",
+                "items": { "type": ["integer", "null"], "format": "uint32", "minimum": 0.0 }
+            })),
+            "(number | null)[]"
+        );
+
+        // `(f32, String)`
+        assert_eq!(
+            ts_type(&json!({
+                "title": "Tuple_of_float_and_String",
+                "type": "array",
+                "items": [{ "type": "number", "format": "float" }, { "type": "string" }],
+                "maxItems": 2,
+                "minItems": 2
+            })),
+            "[number, string]"
+        );
+
+        // `HashMap<String, f64>`
+        assert_eq!(
+            ts_type(&json!({
+                "title": "Map_of_double",
+                "type": "object",
+                "additionalProperties": { "type": "number", "format": "double" }
+            })),
+            "{ [key: string]: number }"
+        );
+
+        // `enum Action { Idle, Move { x: f32 } }`, with a referenced type
+        assert_eq!(
+            ts_type(&json!({
+                "title": "Action",
+                "oneOf": [
+                    { "type": "string", "enum": ["Idle"] },
+                    {
+                        "type": "object",
+                        "required": ["Move"],
+                        "properties": { "Move": { "$ref": "#/definitions/Position" } },
+                        "additionalProperties": false
+                    }
+                ]
+            })),
+            r#""Idle" | { Move: Position }"#
+        );
+    }
+
+    #[test]
+    fn test_is_named() {
+        assert!(is_named(&login_request(), "LoginRequest"));
+        assert!(is_named(&direction(), "Direction"));
+
+        // primitives and collections are inlined
+        assert!(!is_named(&unit(), "()"));
+        assert!(!is_named(
+            &json!({ "title": "String", "type": "string" }),
+            "String"
+        ));
+        assert!(!is_named(&login_request(), "Login"));
+    }
+
+    #[test]
+    fn test_arguments() {
+        assert_eq!(
+            arguments(
+                WebviewRpcParams::First,
+                String::from("LoginRequest"),
+                accepts_null(&login_request())
+            ),
+            "[payload: LoginRequest]"
+        );
+
+        // `rpc.notify("close")`
+        assert_eq!(
+            arguments(
+                WebviewRpcParams::First,
+                ts_type(&unit()),
+                accepts_null(&unit())
+            ),
+            "[payload?: null]"
+        );
+
+        assert_eq!(
+            arguments(WebviewRpcParams::Named, String::from("LoginRequest"), false),
+            "[params: LoginRequest]"
+        );
+        assert_eq!(
+            arguments(
+                WebviewRpcParams::Tuple,
+                String::from("[number, string]"),
+                false
+            ),
+            "[number, string]"
+        );
+        assert_eq!(
+            arguments(WebviewRpcParams::Unit, ts_type(&unit()), true),
+            "[]"
+        );
+    }
+}
@@ -1,3 +1,6 @@
+mod outbox;
+mod recorder;
+mod render_state;
 mod runner_inner;
 mod webview_initializer;
 mod webview_thread;
@@ -0,0 +1,49 @@
+use std::collections::VecDeque;
+
+use crate::{WebviewOutboxDropPolicy, WebviewOutboxSettings};
+
+/// Output event scripts of a webview, held until the page RPC is initialized
+#[derive(Debug)]
+pub(crate) struct Outbox {
+    settings: WebviewOutboxSettings,
+    scripts: VecDeque<String>,
+    /// Events dropped since the last flush
+    dropped: usize,
+}
+
+impl Outbox {
+    pub fn new(settings: WebviewOutboxSettings) -> Self {
+        Self {
+            settings,
+            scripts: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Queue a script, returns `true` if this is the first event dropped since the last flush
+    pub fn push(&mut self, script: String) -> bool {
+        if self.scripts.len() < self.settings.capacity {
+            self.scripts.push_back(script);
+            return false;
+        }
+
+        match self.settings.drop_policy {
+            WebviewOutboxDropPolicy::DropOldest => {
+                self.scripts.pop_front();
+                if self.settings.capacity > 0 {
+                    self.scripts.push_back(script);
+                }
+            }
+            WebviewOutboxDropPolicy::DropNewest => {}
+        }
+
+        self.dropped += 1;
+        self.dropped == 1
+    }
+
+    /// Take the queued scripts in order, and the count of events dropped meanwhile
+    pub fn flush(&mut self) -> (Vec<String>, usize) {
+        let dropped = std::mem::take(&mut self.dropped);
+        (self.scripts.drain(..).collect(), dropped)
+    }
+}
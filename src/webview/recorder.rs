@@ -0,0 +1,233 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::{bounded, Sender, TrySendError};
+use headless_webview::types::{Texture, TextureRegion};
+use serde::Serialize;
+
+use crate::{WebviewRecording, WebviewRecordingFormat};
+
+/// Name of the frame timing sidecar file, written next to the frames
+const METADATA_FILE: &str = "frames.json";
+
+/// Name of the Y4M stream file
+const Y4M_FILE: &str = "frames.y4m";
+
+/// Frames waiting to be written, further frames are dropped until the writer catches up
+const QUEUED_FRAMES: usize = 8;
+
+/// Records the frames of a webview into a directory, runs on the runner thread
+///
+/// Frames are encoded and written on a separate thread, so that recording does not stall the
+/// webview rendering.
+pub(crate) struct Recorder {
+    started: Instant,
+    /// The current frame in full, damaged textures are drawn over it
+    frame: Texture,
+    /// Stream size is fixed
+    y4m: bool,
+    dropped_frames: usize,
+    frame_tx: Sender<(Texture, f64)>,
+    writer: JoinHandle<io::Result<RecordingWriter>>,
+}
+
+/// Frame encoding, runs on the writer thread
+struct RecordingWriter {
+    directory: PathBuf,
+    metadata: RecordingMetadata,
+    y4m: Option<BufWriter<File>>,
+}
+
+#[derive(Serialize)]
+struct RecordingMetadata {
+    format: &'static str,
+    started_at_unix_ms: u128,
+    duration_ms: f64,
+    /// Frames not recorded, as the writer could not keep up
+    dropped_frames: usize,
+    frames: Vec<FrameMetadata>,
+}
+
+#[derive(Serialize)]
+struct FrameMetadata {
+    index: usize,
+    /// File of the frame, `None` for Y4M (frames are in order in the stream)
+    file: Option<String>,
+    /// Time since the start of the recording
+    timestamp_ms: f64,
+    width: u32,
+    height: u32,
+}
+
+impl Recorder {
+    /// Start recording from `frame`, a full texture of the current page
+    pub fn start(
+        recording: &WebviewRecording,
+        directory: PathBuf,
+        frame: Texture,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+
+        let y4m = match recording.format {
+            WebviewRecordingFormat::Png => None,
+            WebviewRecordingFormat::Y4m { fps } => {
+                let mut file = BufWriter::new(File::create(directory.join(Y4M_FILE))?);
+                // 4:4:4 without subsampling, the frame size is fixed for the whole stream
+                writeln!(
+                    file,
+                    "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+                    frame.width, frame.height, fps
+                )?;
+                Some(file)
+            }
+        };
+
+        let mut writer = RecordingWriter {
+            directory,
+            metadata: RecordingMetadata {
+                format: match recording.format {
+                    WebviewRecordingFormat::Png => "png",
+                    WebviewRecordingFormat::Y4m { .. } => "y4m",
+                },
+                started_at_unix_ms: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis()),
+                duration_ms: 0.,
+                dropped_frames: 0,
+                frames: Vec::new(),
+            },
+            y4m,
+        };
+
+        let (frame_tx, frame_rx) = bounded::<(Texture, f64)>(QUEUED_FRAMES);
+        let mut recorder = Self {
+            started: Instant::now(),
+            y4m: writer.y4m.is_some(),
+            dropped_frames: 0,
+            frame_tx,
+            writer: thread::Builder::new()
+                .name("webview_recorder".to_string())
+                .spawn(move || {
+                    for (frame, timestamp_ms) in frame_rx.iter() {
+                        writer.write_frame(&frame, timestamp_ms)?;
+                    }
+
+                    Ok(writer)
+                })?,
+            frame,
+        };
+
+        recorder.queue_frame()?;
+
+        Ok(recorder)
+    }
+
+    /// Record a texture emitted by the webview
+    pub fn record(&mut self, texture: &Texture) -> io::Result<()> {
+        if !self.frame.apply(texture) {
+            // resized: continue from the next full texture, unless the stream size is fixed (Y4M)
+            if texture.region != TextureRegion::Full || self.y4m {
+                return Ok(());
+            }
+
+            self.frame = texture.clone();
+        }
+
+        self.queue_frame()
+    }
+
+    /// Stop recording, waits for the queued frames and writes the frame timing metadata
+    pub fn finish(self) -> io::Result<PathBuf> {
+        let duration_ms = self.started.elapsed().as_secs_f64() * 1000.;
+
+        drop(self.frame_tx);
+        let mut writer = self
+            .writer
+            .join()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "recording writer panicked"))??;
+
+        if let Some(mut y4m) = writer.y4m.take() {
+            y4m.flush()?;
+        }
+
+        writer.metadata.duration_ms = duration_ms;
+        writer.metadata.dropped_frames = self.dropped_frames;
+
+        let file = File::create(writer.directory.join(METADATA_FILE))?;
+        serde_json::to_writer_pretty(file, &writer.metadata)?;
+
+        Ok(writer.directory)
+    }
+
+    fn queue_frame(&mut self) -> io::Result<()> {
+        let timestamp_ms = self.started.elapsed().as_secs_f64() * 1000.;
+
+        match self.frame_tx.try_send((self.frame.clone(), timestamp_ms)) {
+            Ok(_) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                self.dropped_frames += 1;
+                Ok(())
+            }
+            // the writer failed, its error is returned by `finish`
+            Err(TrySendError::Disconnected(_)) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "recording writer stopped",
+            )),
+        }
+    }
+}
+
+impl RecordingWriter {
+    fn write_frame(&mut self, frame: &Texture, timestamp_ms: f64) -> io::Result<()> {
+        let index = self.metadata.frames.len();
+
+        let file = match &mut self.y4m {
+            Some(y4m) => {
+                y4m.write_all(b"FRAME\n")?;
+                write_yuv444(y4m, frame)?;
+                None
+            }
+            None => {
+                let file = format!("frame_{:06}.png", index);
+                let png = frame
+                    .encode_png()
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+                fs::write(self.directory.join(&file), png)?;
+                Some(file)
+            }
+        };
+
+        self.metadata.frames.push(FrameMetadata {
+            index,
+            file,
+            timestamp_ms,
+            width: frame.width,
+            height: frame.height,
+        });
+
+        Ok(())
+    }
+}
+
+/// Write the planes of a full texture as limited range BT.601 YUV, alpha is dropped
+fn write_yuv444<W: Write>(writer: &mut W, texture: &Texture) -> io::Result<()> {
+    let n_channels = texture.format.n_channels();
+    let pixels = texture.data.chunks_exact(n_channels);
+
+    let mut planes = vec![0u8; pixels.len() * 3];
+    let (y_plane, uv_planes) = planes.split_at_mut(pixels.len());
+    let (u_plane, v_plane) = uv_planes.split_at_mut(pixels.len());
+
+    for (i, pixel) in pixels.enumerate() {
+        let (r, g, b) = (pixel[0] as f32, pixel[1] as f32, pixel[2] as f32);
+
+        y_plane[i] = (16. + (65.738 * r + 129.057 * g + 25.064 * b) / 256.).round() as u8;
+        u_plane[i] = (128. + (-37.945 * r - 74.494 * g + 112.439 * b) / 256.).round() as u8;
+        v_plane[i] = (128. + (112.439 * r - 94.154 * g - 18.285 * b) / 256.).round() as u8;
+    }
+
+    writer.write_all(&planes)
+}
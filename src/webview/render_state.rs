@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+use crate::{WebviewRenderMode, WebviewRenderSettings};
+
+/// How long on-demand webviews keep being captured after input or navigation, so that the page
+/// has time to repaint (transitions, page loads etc.)
+pub(crate) const ON_DEMAND_CAPTURE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Per-webview capture scheduling of the runner thread
+#[derive(Debug, Default)]
+pub(crate) struct RenderState {
+    pub settings: WebviewRenderSettings,
+    last_capture: Option<Instant>,
+    capture_until: Option<Instant>,
+    frame_requested: bool,
+}
+
+impl RenderState {
+    /// Content may change by input or navigation, wakes up on-demand webviews
+    pub fn wake(&mut self, now: Instant) {
+        self.capture_until = Some(now + ON_DEMAND_CAPTURE_WINDOW);
+    }
+
+    /// Capture once on the next tick, regardless of the mode
+    pub fn request_frame(&mut self) {
+        self.frame_requested = true;
+    }
+
+    /// Whether the webview should be captured on this tick
+    pub fn should_capture(&self, now: Instant) -> bool {
+        let scheduled = self.frame_requested
+            || match self.settings.mode {
+                WebviewRenderMode::Continuous => true,
+                WebviewRenderMode::OnDemand => self.capture_until.map_or(false, |t| now <= t),
+                WebviewRenderMode::Paused => false,
+            };
+
+        scheduled && !self.is_throttled(now)
+    }
+
+    /// The webview was ticked for a capture, any requested frame has been taken (if the page
+    /// changed at all)
+    pub fn ticked(&mut self) {
+        self.frame_requested = false;
+    }
+
+    /// A new texture was captured, starts the frame rate limit interval
+    pub fn captured(&mut self, now: Instant) {
+        self.last_capture = Some(now);
+    }
+
+    fn is_throttled(&self, now: Instant) -> bool {
+        match (self.settings.max_fps, self.last_capture) {
+            (Some(max_fps), Some(last_capture)) if max_fps > 0. => {
+                now.duration_since(last_capture) < Duration::from_secs_f32(1. / max_fps)
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,17 +1,28 @@
+use bevy::input::mouse::MouseScrollUnit;
 use bevy::log;
 
 use bevy::prelude::Entity;
 use crossbeam_channel::{Receiver, Sender};
 use std::collections::HashMap;
+use std::time::Instant;
 
 use crate::events::InputEvent;
-use crate::types::{TextureReceivedEvent, WebviewAction};
-use crate::WebviewCommand;
+use crate::input::{to_webview_key_code, to_webview_mouse_button};
+use crate::types::{LaunchEvent, TextureReceivedEvent, WebviewAction};
+use crate::webview::outbox::Outbox;
+use crate::webview::recorder::Recorder;
+use crate::webview::render_state::RenderState;
+use crate::{
+    WebviewCaptured, WebviewCommand, WebviewCursorChanged, WebviewFrameBuffers,
+    WebviewOutboxSettings,
+};
 
 use headless_webview::prelude::*;
 use headless_webview::types::{
-    ElementState, KeyboardInput, MouseButton, MouseEvent, TickMode, WindowSize,
+    ElementState, KeyboardInput, MouseEvent, MouseMotion, ScrollEvent, ScrollUnit, SnapshotRegion,
+    TickMode, TouchEvent, TouchPhase,
 };
+use headless_webview::webview::{binary_output_event_script, output_event_script};
 
 struct WebViewInner<T> {
     is_rpc_initialized: bool,
@@ -21,22 +32,71 @@ struct WebViewInner<T> {
 /// Webview runner thread
 ///
 /// Communicates through events
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
     texture_tx: Sender<TextureReceivedEvent>,
     webview_action_rx: Receiver<WebviewAction>,
     input_event_tx: Sender<InputEvent>,
+    cursor_tx: Sender<WebviewCursorChanged>,
+    capture_tx: Sender<WebviewCaptured>,
+    frame_buffers: WebviewFrameBuffers,
+    outbox_settings: WebviewOutboxSettings,
     webview_implementation: fn() -> WindowBuilder<T>,
 ) {
     let mut webviews: HashMap<Entity, WebViewInner<<T as HeadlessWindow>::Webview>> =
         HashMap::new();
+    // kept apart from the webviews, as the settings may arrive before the launch
+    let mut render_states: HashMap<Entity, RenderState> = HashMap::new();
+    let mut recorders: HashMap<Entity, Recorder> = HashMap::new();
+    // output events of launched webviews until the page RPC is initialized. Output events are sent
+    // after the launch of new webviews (see `add_webview_output_event`)
+    let mut outboxes: HashMap<Entity, Outbox> = HashMap::new();
 
     for event in webview_action_rx.iter() {
+        // input and navigation may change the content, wake up on-demand webviews
+        match &event {
+            WebviewAction::Launch(LaunchEvent { entity, .. })
+            | WebviewAction::MouseMotion((entity, ..))
+            | WebviewAction::Click((entity, ..))
+            | WebviewAction::Scroll((entity, ..))
+            | WebviewAction::Touch((entity, ..))
+            | WebviewAction::TypeKeyboard((entity, ..))
+            | WebviewAction::TypeText((entity, ..))
+            | WebviewAction::Resize((entity, ..))
+            | WebviewAction::SetFocus(entity, ..)
+            | WebviewAction::SendRpcResponse(entity, ..)
+            | WebviewAction::Navigate(entity, ..) => {
+                render_states
+                    .entry(*entity)
+                    .or_default()
+                    .wake(Instant::now());
+            }
+            WebviewAction::RunCommand(entity, command) => {
+                let now = Instant::now();
+
+                for (e, _) in webviews.iter().filter(filter_entity(*entity)) {
+                    let state = render_states.entry(*e).or_default();
+
+                    match command {
+                        WebviewCommand::RequestFrame => state.request_frame(),
+                        _ => state.wake(now),
+                    }
+                }
+            }
+            _ => {}
+        }
+
         match event {
             WebviewAction::Launch(launch_event) => {
                 log::debug!("Webview {:?}: launch webview instance", launch_event.entity);
                 let window_builder = webview_implementation();
-                let webview = launch_event.to_webview(window_builder, input_event_tx.clone());
+                let webview = launch_event.to_webview(
+                    window_builder,
+                    input_event_tx.clone(),
+                    cursor_tx.clone(),
+                );
 
+                frame_buffers.insert(launch_event.entity);
                 webviews.insert(
                     launch_event.entity,
                     WebViewInner {
@@ -46,64 +106,131 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                 );
             }
 
-            WebviewAction::SetRPCInitialized(entity) => {
-                log::debug!("Webview {:?}: set is_rpc_initialized", entity);
+            WebviewAction::SetRPCInitialized(entity, initialized) => {
+                log::debug!(
+                    "Webview {:?}: set is_rpc_initialized={}",
+                    entity,
+                    initialized
+                );
 
                 if let Some(w) = webviews.get_mut(&entity) {
-                    w.is_rpc_initialized = true;
+                    w.is_rpc_initialized = initialized;
+
+                    if let (true, Some(outbox)) = (initialized, outboxes.get_mut(&entity)) {
+                        let (scripts, dropped) = outbox.flush();
+
+                        if dropped > 0 {
+                            log::warn!(
+                                "Webview {:?}: {} output events were dropped before the page RPC was initialized",
+                                entity,
+                                dropped
+                            );
+                        }
+
+                        for script in scripts {
+                            w.webview.evaluate_script(&script).unwrap();
+                        }
+                    }
                 }
             }
 
-            WebviewAction::MouseMotion((entity, position)) => {
-                log::debug!("Webview {:?}: mouse motion={:?}", entity, position);
+            WebviewAction::MouseMotion((entity, position, buttons, modifiers)) => {
+                log::debug!(
+                    "Webview {:?}: mouse motion={:?} buttons={:?}",
+                    entity,
+                    position,
+                    buttons
+                );
+
                 if let Some(w) = webviews.get(&entity) {
-                    w.webview.send_mouse_position(
-                        // TODO: move position calc to the webview lib?
-                        headless_webview::types::Vec2::new(
-                            position.x * w.webview.window().width() as f32,
-                            w.webview.window().height() as f32
-                                - position.y * w.webview.window().height() as f32,
-                        ),
-                    );
+                    w.webview.send_mouse_motion(MouseMotion {
+                        position: window_position(&w.webview, position),
+                        buttons: buttons.into_iter().map(to_webview_mouse_button).collect(),
+                        modifiers,
+                    });
                 }
             }
 
-            WebviewAction::Click((entity, button, element_state, position)) => {
+            WebviewAction::Click((entity, button, element_state, position, modifiers)) => {
                 log::debug!(
-                    "Webview {:?}: mouse={:?} state={:?} position={:?}",
+                    "Webview {:?}: mouse={:?} state={:?} position={:?} modifiers={:?}",
                     entity,
                     button,
                     element_state,
-                    position
+                    position,
+                    modifiers
                 );
 
                 if let Some(w) = webviews.get(&entity) {
                     w.webview.send_mouse_event(MouseEvent {
-                        button: match button {
-                            bevy::prelude::MouseButton::Left => MouseButton::Left,
-                            bevy::prelude::MouseButton::Right => MouseButton::Right,
-                            bevy::prelude::MouseButton::Middle => MouseButton::Middle,
-                            bevy::prelude::MouseButton::Other(_) => continue,
-                        },
+                        button: to_webview_mouse_button(button),
                         state: match element_state {
                             bevy::input::ElementState::Pressed => ElementState::Pressed,
                             bevy::input::ElementState::Released => ElementState::Released,
                         },
 
-                        // TODO: move position calc to the webview lib?
-                        position: headless_webview::types::Vec2::new(
-                            position.x * w.webview.window().width() as f32,
-                            w.webview.window().height() as f32
-                                - position.y * w.webview.window().height() as f32,
-                        ),
+                        position: window_position(&w.webview, position),
+                        modifiers,
+                    });
+                }
+            }
+
+            WebviewAction::Scroll((entity, unit, scroll, position, modifiers)) => {
+                log::debug!(
+                    "Webview {:?}: scroll={:?} unit={:?} position={:?}",
+                    entity,
+                    scroll,
+                    unit,
+                    position
+                );
+
+                if let Some(w) = webviews.get(&entity) {
+                    w.webview.send_scroll(ScrollEvent {
+                        // Bevy wheel movement is towards the top-left of the content
+                        delta: headless_webview::types::Vec2::new(-scroll.x, -scroll.y),
+                        unit: match unit {
+                            MouseScrollUnit::Line => ScrollUnit::Line,
+                            MouseScrollUnit::Pixel => ScrollUnit::Pixel,
+                        },
+                        position: window_position(&w.webview, position),
+                        modifiers,
+                    });
+                }
+            }
+
+            WebviewAction::Touch((entity, id, phase, position)) => {
+                log::debug!(
+                    "Webview {:?}: touch id={} phase={:?} position={:?}",
+                    entity,
+                    id,
+                    phase,
+                    position
+                );
+
+                if let Some(w) = webviews.get(&entity) {
+                    w.webview.send_touch_event(TouchEvent {
+                        id,
+                        phase: match phase {
+                            bevy::input::touch::TouchPhase::Started => TouchPhase::Started,
+                            bevy::input::touch::TouchPhase::Moved => TouchPhase::Moved,
+                            bevy::input::touch::TouchPhase::Ended => TouchPhase::Ended,
+                            bevy::input::touch::TouchPhase::Cancelled => TouchPhase::Cancelled,
+                        },
+                        position: window_position(&w.webview, position),
                     });
                 }
             }
 
             WebviewAction::Hover((_entity, _position)) => {}
 
-            WebviewAction::TypeKeyboard((entity, keyboard_input)) => {
-                log::debug!("Webview {:?}: keyboard event", entity);
+            WebviewAction::TypeKeyboard((entity, keyboard_input, modifiers, text)) => {
+                log::debug!(
+                    "Webview {:?}: keyboard event key={:?} scan_code={} state={:?}",
+                    entity,
+                    keyboard_input.key_code,
+                    keyboard_input.scan_code,
+                    keyboard_input.state
+                );
 
                 if let Some(w) = webviews.get(&entity) {
                     w.webview.send_keyboard_input(KeyboardInput {
@@ -111,21 +238,45 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                             bevy::input::ElementState::Pressed => ElementState::Pressed,
                             bevy::input::ElementState::Released => ElementState::Released,
                         },
+                        scan_code: keyboard_input.scan_code,
+                        key_code: keyboard_input.key_code.map(to_webview_key_code),
+                        modifiers,
+                        text,
                     })
                 }
             }
 
+            WebviewAction::TypeText((entity, text)) => {
+                log::debug!("Webview {:?}: text input ({} bytes)", entity, text.len());
+
+                if let Some(w) = webviews.get(&entity) {
+                    w.webview.send_text_input(&text);
+                }
+            }
+
+            WebviewAction::SetFocus(entity, focused) => {
+                log::debug!("Webview {:?}: focused={}", entity, focused);
+
+                if let Some(w) = webviews.get(&entity) {
+                    w.webview.set_focus(focused);
+                }
+            }
+
+            WebviewAction::Navigate(entity, action) => {
+                log::debug!("Webview {:?}: navigate {:?}", entity, action);
+
+                if let Some(w) = webviews.get(&entity) {
+                    w.webview.evaluate_script(action.script()).unwrap();
+                }
+            }
+
             WebviewAction::Resize((entity, size)) => {
                 log::debug!("Webview {:?}: resized to {:?}", entity, size);
 
                 if let Some(w) = webviews.get_mut(&entity) {
                     // TODO: this check can be removed after https://github.com/bevyengine/bevy/pull/3785 is merged
-                    if w.webview.window().width() as f32 != size.x
-                        || w.webview.window().height() as f32 != size.y
-                    {
-                        w.webview
-                            .resize(WindowSize::new(size.x as u32, size.y as u32))
-                            .unwrap();
+                    if w.webview.inner_size() != size {
+                        w.webview.resize(size).unwrap();
                     }
                 }
             }
@@ -134,6 +285,12 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                 log::debug!("Webview {:?}: removed", entity);
 
                 let _ = webviews.remove(&entity);
+                render_states.remove(&entity);
+                outboxes.remove(&entity);
+                if let Some(recorder) = recorders.remove(&entity) {
+                    finish_recording(entity, recorder);
+                }
+                frame_buffers.remove(entity);
             }
 
             // Received RPC event, call Javascript in the webview
@@ -145,27 +302,67 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                     value.as_bytes().len()
                 );
 
-                let script = format!(
-                    "window.external.rpc._message({:?}, JSON.parse({:?}));",
-                    method, value
+                let script = output_event_script(&method, &value);
+                send_output_script(entity, script, &webviews, &mut outboxes, outbox_settings);
+            }
+
+            WebviewAction::SendBinaryEvent(entity, method, bytes) => {
+                log::debug!(
+                    "Webview {:?}: call RPC method {:?} ({} byte binary payload)",
+                    entity,
+                    method,
+                    bytes.len()
                 );
 
-                webviews
-                    .iter()
-                    .filter(filter_entity(entity))
-                    .filter(|(_, w)| w.is_rpc_initialized)
-                    .for_each(|(_, w)| {
-                        w.webview.evaluate_script(&script).unwrap();
-                    });
+                let script = binary_output_event_script(&method, &bytes);
+                send_output_script(entity, script, &webviews, &mut outboxes, outbox_settings);
+            }
+
+            WebviewAction::SendRpcResponse(entity, response) => {
+                log::debug!("Webview {:?}: RPC response {:?}", entity, response);
+
+                if let Some(w) = webviews.get(&entity) {
+                    match response.into_script() {
+                        Ok(Some(script)) => w.webview.evaluate_script(&script).unwrap(),
+                        Ok(None) => {}
+                        Err(e) => log::warn!("Webview {:?}: RPC response error: {}", entity, e),
+                    }
+                }
             }
 
             WebviewAction::Tick => {
                 let mut texture_count = 0;
+                let now = Instant::now();
 
                 for (entity, w) in webviews.iter_mut() {
+                    let render_state = render_states.entry(*entity).or_default();
+
+                    // always ticked, the page keeps loading and handling input and RPC, only the
+                    // capture is skipped
                     w.webview.tick(TickMode::Immediate);
 
-                    if let Ok(Some(texture)) = w.webview.get_texture() {
+                    if !render_state.should_capture(now) {
+                        continue;
+                    }
+
+                    render_state.ticked();
+
+                    let mut buffer = frame_buffers.take(*entity);
+                    let capacity = buffer.capacity();
+
+                    if let Ok(Some(mut texture)) = w.webview.get_texture_into(&mut buffer) {
+                        render_state.captured(now);
+
+                        // Bevy blends with straight alpha, a no-op for engines emitting it
+                        texture.unpremultiply();
+
+                        if let Some(recorder) = recorders.get_mut(entity) {
+                            if let Err(e) = recorder.record(&texture) {
+                                log::warn!("Webview {:?}: recording failed: {}", entity, e);
+                            }
+                        }
+                        frame_buffers.count_frame(capacity, texture.data.capacity());
+
                         match texture_tx.send(TextureReceivedEvent {
                             entity: *entity,
                             texture,
@@ -174,6 +371,8 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                             Err(e) => log::warn!("Could not send webview texture: {:?}", e),
                         }
                     }
+
+                    frame_buffers.recycle(*entity, buffer);
                 }
 
                 log::trace!("Webview(s) tick done, {} new textures", texture_count);
@@ -184,29 +383,37 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
 
                 webviews.values_mut().for_each(|w| w.webview.close());
 
+                recorders
+                    .drain()
+                    .for_each(|(entity, recorder)| finish_recording(entity, recorder));
+
                 break;
             }
 
             WebviewAction::RunCommand(entity, command) => {
                 log::debug!("Webview ({:?}) command: {:?}", entity, command);
 
-                let filtered_webviews = webviews.iter().filter(filter_entity(entity));
+                let filtered_webviews = webviews.iter_mut().filter(filter_entity(entity));
 
                 match command {
+                    // output events are buffered until the new page has initialized the RPC
                     WebviewCommand::LoadUri(uri) => {
                         filtered_webviews.for_each(|(_, w)| {
+                            w.is_rpc_initialized = false;
                             w.webview.load_uri(uri.clone());
                         });
                     }
 
                     WebviewCommand::LoadHtml(html) => {
                         filtered_webviews.for_each(|(_, w)| {
+                            w.is_rpc_initialized = false;
                             w.webview.load_html(html.clone());
                         });
                     }
 
                     WebviewCommand::Reload => {
                         filtered_webviews.for_each(|(_, w)| {
+                            w.is_rpc_initialized = false;
                             w.webview.reload();
                         });
                     }
@@ -216,9 +423,105 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
                             w.webview.evaluate_script(&javascript).unwrap();
                         });
                     }
+
+                    // scheduled above, captured on the next tick
+                    WebviewCommand::RequestFrame => {}
+
+                    WebviewCommand::Capture { full_page } => {
+                        let region = match full_page {
+                            true => SnapshotRegion::FullDocument,
+                            false => SnapshotRegion::Visible,
+                        };
+
+                        filtered_webviews.for_each(|(entity, w)| {
+                            match w.webview.snapshot(region) {
+                                Ok(image) => capture_tx
+                                    .send(WebviewCaptured {
+                                        entity: *entity,
+                                        image,
+                                    })
+                                    .unwrap(),
+                                Err(e) => {
+                                    log::warn!("Webview {:?}: capture failed: {}", entity, e)
+                                }
+                            }
+                        });
+                    }
+
+                    WebviewCommand::StartRecording(recording) => {
+                        filtered_webviews.for_each(|(webview_entity, w)| {
+                            // several webviews may record at once, keep them apart
+                            let directory = match entity {
+                                Some(_) => recording.directory.clone(),
+                                None => recording
+                                    .directory
+                                    .join(format!("webview_{}", webview_entity.id())),
+                            };
+
+                            // damaged textures are recorded on top of a full frame
+                            let recorder = w
+                                .webview
+                                .snapshot(SnapshotRegion::Visible)
+                                .map_err(|e| e.to_string())
+                                .and_then(|frame| {
+                                    Recorder::start(&recording, directory, frame)
+                                        .map_err(|e| e.to_string())
+                                });
+
+                            match recorder {
+                                Ok(recorder) => {
+                                    log::info!("Webview {:?}: recording started", webview_entity);
+
+                                    if let Some(previous) =
+                                        recorders.insert(*webview_entity, recorder)
+                                    {
+                                        finish_recording(*webview_entity, previous);
+                                    }
+                                }
+                                Err(e) => {
+                                    log::warn!(
+                                        "Webview {:?}: could not start recording: {}",
+                                        webview_entity,
+                                        e
+                                    )
+                                }
+                            }
+                        });
+                    }
+
+                    WebviewCommand::StopRecording => {
+                        filtered_webviews.for_each(|(webview_entity, _)| {
+                            if let Some(recorder) = recorders.remove(webview_entity) {
+                                finish_recording(*webview_entity, recorder);
+                            }
+                        });
+                    }
                 }
             }
 
+            WebviewAction::SendFullFrame(entity) => {
+                if let Some(w) = webviews.get_mut(&entity) {
+                    match w.webview.snapshot(SnapshotRegion::Visible) {
+                        Ok(mut texture) => {
+                            texture.unpremultiply();
+
+                            if let Err(e) =
+                                texture_tx.send(TextureReceivedEvent { entity, texture })
+                            {
+                                log::warn!("Could not send webview texture: {:?}", e);
+                            }
+                        }
+                        Err(e) => log::warn!("Webview {:?}: full frame failed: {}", entity, e),
+                    }
+                }
+            }
+
+            WebviewAction::SetRenderSettings(entity, settings) => {
+                log::debug!("Webview {:?}: render settings {:?}", entity, settings);
+
+                render_states.entry(entity).or_default().settings = settings;
+            }
+
             WebviewAction::SetVisibility(entity, is_visible) => {
                 if let Some(w) = webviews.get_mut(&entity) {
                     w.webview.set_is_visible(is_visible);
@@ -228,9 +531,142 @@ pub(crate) fn webview_runner_inner<T: HeadlessWindow>(
     }
 }
 
+/// Evaluate an output event script in the target webview(s), or queue it until the page RPC is
+/// initialized
+fn send_output_script<W: EngineWebview>(
+    entity: Option<Entity>,
+    script: String,
+    webviews: &HashMap<Entity, WebViewInner<W>>,
+    outboxes: &mut HashMap<Entity, Outbox>,
+    outbox_settings: WebviewOutboxSettings,
+) {
+    let targets: Vec<Entity> = match entity {
+        Some(entity) => vec![entity],
+        None => webviews.keys().copied().collect(),
+    };
+
+    for target in targets {
+        match webviews.get(&target) {
+            Some(w) if w.is_rpc_initialized => {
+                w.webview.evaluate_script(&script).unwrap();
+            }
+
+            // the page is loading
+            Some(_) => {
+                let outbox = outboxes
+                    .entry(target)
+                    .or_insert_with(|| Outbox::new(outbox_settings));
+
+                if outbox.push(script.clone()) {
+                    log::warn!(
+                        "Webview {:?}: outbox is full ({} events), dropping output events until the page RPC is initialized",
+                        target,
+                        outbox_settings.capacity
+                    );
+                }
+            }
+
+            // removed, or never launched (no size)
+            None => log::debug!("Webview {:?}: not launched, output event dropped", target),
+        }
+    }
+}
+
+fn finish_recording(entity: Entity, recorder: Recorder) {
+    match recorder.finish() {
+        Ok(directory) => log::info!("Webview {:?}: recording saved to {:?}", entity, directory),
+        Err(e) => log::warn!("Webview {:?}: could not finish recording: {}", entity, e),
+    }
+}
+
 fn filter_entity<T>(entity: Option<Entity>) -> impl FnMut(&(&Entity, T)) -> bool {
     move |(e, _)| match entity {
         Some(entity) => **e == entity,
         None => true,
     }
 }
+
+/// Convert a relative (0..1, bottom-up) node offset into webview pixel coordinates
+///
+/// The window is sized in device pixels (see `WindowSize::scale_factor`), so are the coordinates
+// TODO: move position calc to the webview lib?
+fn window_position<W: EngineWebview>(
+    webview: &W,
+    offset: bevy::prelude::Vec2,
+) -> headless_webview::types::Vec2 {
+    let (width, height) = (
+        webview.window().width() as f32,
+        webview.window().height() as f32,
+    );
+
+    headless_webview::types::Vec2::new(offset.x * width, height - offset.y * height)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crossbeam_channel::unbounded;
+    use headless_webview::{engines, types::WindowSize};
+
+    use super::*;
+    use crate::webview::render_state::ON_DEMAND_CAPTURE_WINDOW;
+    use crate::{Webview, WebviewRenderMode, WebviewRenderSettings};
+
+    #[test]
+    fn test_on_demand_webview_delivers_input_events() {
+        let (webview_action_tx, webview_action_rx) = unbounded();
+        let (texture_tx, texture_rx) = unbounded();
+        let (input_event_tx, input_event_rx) = unbounded();
+        let (cursor_tx, _cursor_rx) = unbounded();
+        let (capture_tx, _capture_rx) = unbounded();
+
+        let runner = thread::spawn(move || {
+            webview_runner_inner(
+                texture_tx,
+                webview_action_rx,
+                input_event_tx,
+                cursor_tx,
+                capture_tx,
+                WebviewFrameBuffers::default(),
+                WebviewOutboxSettings::default(),
+                engines::dummy,
+            )
+        });
+
+        let entity = Entity::from_raw(1);
+        for action in [
+            WebviewAction::SetRenderSettings(
+                entity,
+                WebviewRenderSettings {
+                    mode: WebviewRenderMode::OnDemand,
+                    ..Default::default()
+                },
+            ),
+            WebviewAction::Launch(LaunchEvent {
+                entity,
+                webview: Webview::default(),
+                size: WindowSize::new(8, 8),
+                spatial_navigation: false,
+            }),
+        ] {
+            webview_action_tx.send(action).unwrap();
+        }
+
+        // the launch wake-up is over, the page loads (and sends `initialize`) after it
+        thread::sleep(ON_DEMAND_CAPTURE_WINDOW + std::time::Duration::from_millis(100));
+
+        webview_action_tx.send(WebviewAction::Tick).unwrap();
+        webview_action_tx.send(WebviewAction::AppExit).unwrap();
+        runner.join().unwrap();
+
+        let methods: Vec<String> = input_event_rx
+            .try_iter()
+            .map(|event| event.request.method)
+            .collect();
+        assert_eq!(methods, vec![String::from("_webview")]);
+
+        // ticked, but not captured
+        assert_eq!(texture_rx.try_iter().count(), 0);
+    }
+}
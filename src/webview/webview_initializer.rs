@@ -2,12 +2,14 @@ use bevy::log;
 use bevy::prelude::Color;
 use crossbeam_channel::Sender;
 use headless_webview::http::{Request, Response, ResponseBuilder};
-use headless_webview::webview::{RpcRequest, RpcResponse};
+use headless_webview::webview::RpcRequest;
 use std::fs::{canonicalize, read};
 use std::path::PathBuf;
 
 use crate::events::InputEvent;
+use crate::input::from_webview_cursor_icon;
 use crate::types::LaunchEvent;
+use crate::WebviewCursorChanged;
 
 use headless_webview::prelude::*;
 
@@ -18,6 +20,7 @@ impl LaunchEvent {
         &self,
         window_builder: WindowBuilder<T>,
         input_event_tx: Sender<InputEvent>,
+        cursor_tx: Sender<WebviewCursorChanged>,
     ) -> T::Webview {
         let mut window = window_builder;
 
@@ -57,20 +60,28 @@ impl LaunchEvent {
             webview = webview.with_initialization_script(&js);
         }
 
+        webview = webview.with_spatial_navigation(self.spatial_navigation);
+
         let entity_clone = self.entity.clone();
 
         let webview = webview.with_rpc_handler(move |_window, request: RpcRequest| {
             log::trace!("Webview - RPC handler called");
 
-            let id = request.id.clone();
             input_event_tx
                 .send(InputEvent::new(entity_clone, request))
                 .unwrap();
 
-            Some(RpcResponse::new_result(
-                id,
-                Some(serde_json::Value::String(String::from("hello world!"))),
-            ))
+            // answered by the Bevy systems, possibly frames later (`WebviewAction::SendRpcResponse`)
+            None
+        });
+
+        let webview = webview.with_cursor_handler(move |icon| {
+            cursor_tx
+                .send(WebviewCursorChanged {
+                    entity: entity_clone,
+                    icon: from_webview_cursor_icon(icon),
+                })
+                .unwrap();
         });
 
         webview.build().unwrap()
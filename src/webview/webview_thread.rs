@@ -5,15 +5,20 @@ use headless_webview::HeadlessWindow;
 
 use crate::types::EventTransport;
 use crate::webview::webview_runner_inner;
-use crate::WebviewEngine;
+use crate::{WebviewEngine, WebviewFrameBuffers, WebviewOutboxSettings};
 
 /// This acts as a communication bridge between webview implementation and bevy systems
 pub(crate) fn webview_thread<T: 'static + HeadlessWindow>(
     webview_implementation: WebviewEngine<T>,
+    outbox_settings: WebviewOutboxSettings,
 ) -> EventTransport {
     let (webview_action_tx, webview_action_rx) = unbounded();
     let (texture_tx, texture_rx) = unbounded();
     let (input_event_tx, input_event_rx) = unbounded();
+    let (cursor_tx, cursor_rx) = unbounded();
+    let (capture_tx, capture_rx) = unbounded();
+    let frame_buffers = WebviewFrameBuffers::default();
+    let runner_frame_buffers = frame_buffers.clone();
 
     let impl_fn = webview_implementation.0.clone();
 
@@ -25,7 +30,16 @@ pub(crate) fn webview_thread<T: 'static + HeadlessWindow>(
             let _ = (impl_fn)();
 
             // start runner
-            webview_runner_inner(texture_tx, webview_action_rx, input_event_tx, impl_fn);
+            webview_runner_inner(
+                texture_tx,
+                webview_action_rx,
+                input_event_tx,
+                cursor_tx,
+                capture_tx,
+                runner_frame_buffers,
+                outbox_settings,
+                impl_fn,
+            );
         })
         .unwrap();
 
@@ -33,5 +47,8 @@ pub(crate) fn webview_thread<T: 'static + HeadlessWindow>(
         webview_action_tx,
         texture_rx,
         input_event_rx,
+        cursor_rx,
+        capture_rx,
+        frame_buffers,
     }
 }